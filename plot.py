@@ -118,6 +118,56 @@ def plot_proposer_distribution(df, output_dir):
     plt.savefig(os.path.join(output_dir, 'proposer_distribution.png'), dpi=300, bbox_inches='tight')
     plt.close()
 
+def plot_throughput(file_path, output_dir):
+    """Plot confirmed blobs per block and the unconfirmed queue length over time"""
+    df = pd.read_csv(file_path)
+
+    fig, (ax_rate, ax_queue) = plt.subplots(2, 1, figsize=(15, 10), sharex=True)
+
+    ax_rate.scatter(df['Block'], df['Confirmed Blobs'], color='blue', alpha=0.2, s=20)
+    ax_rate.plot(df['Block'], df['Rolling Confirmed Per Block'],
+                 label=f'Rolling Mean ({WINDOW} blocks)', color='blue', linewidth=2)
+    ax_rate.set_ylabel('Confirmed Blobs per Block', fontsize=14)
+    ax_rate.set_title('Blob Throughput', fontsize=16)
+    ax_rate.legend(fontsize=12)
+    ax_rate.grid(True, linestyle='--', alpha=0.7)
+
+    ax_queue.plot(df['Block'], df['Queue Length'], color='orange', linewidth=2)
+    ax_queue.set_xlabel('Block Number', fontsize=14)
+    ax_queue.set_ylabel('Unconfirmed Blobs', fontsize=14)
+    ax_queue.set_title('Unconfirmed Blob Queue Length', fontsize=16)
+    ax_queue.grid(True, linestyle='--', alpha=0.7)
+
+    plt.tight_layout()
+    plt.savefig(os.path.join(output_dir, 'throughput.png'), dpi=300, bbox_inches='tight')
+    plt.close()
+
+def plot_bandwidth(file_path, output_dir):
+    """Plot per-node bandwidth per block and cumulative per-node storage over time"""
+    df = pd.read_csv(file_path)
+
+    fig, (ax_bw, ax_storage) = plt.subplots(2, 1, figsize=(15, 10), sharex=True)
+
+    ax_bw.scatter(df['Block'], df['Mean Node Bytes'] / 1024, color='green', alpha=0.2, s=20)
+    ax_bw.plot(df['Block'], df['Rolling Mean Node Bytes'] / 1024,
+               label=f'Rolling Mean ({WINDOW} blocks)', color='green', linewidth=2)
+    ax_bw.plot(df['Block'], df['Max Node Bytes'] / 1024,
+               label='Busiest Node', color='red', alpha=0.5, linewidth=1)
+    ax_bw.set_ylabel('KiB per Block', fontsize=14)
+    ax_bw.set_title('Per-Node Bandwidth', fontsize=16)
+    ax_bw.legend(fontsize=12)
+    ax_bw.grid(True, linestyle='--', alpha=0.7)
+
+    ax_storage.plot(df['Block'], df['Storage Per Node Bytes'] / 1024 ** 2, color='purple', linewidth=2)
+    ax_storage.set_xlabel('Block Number', fontsize=14)
+    ax_storage.set_ylabel('MiB', fontsize=14)
+    ax_storage.set_title('Storage per Node', fontsize=16)
+    ax_storage.grid(True, linestyle='--', alpha=0.7)
+
+    plt.tight_layout()
+    plt.savefig(os.path.join(output_dir, 'bandwidth.png'), dpi=300, bbox_inches='tight')
+    plt.close()
+
 def print_statistics(df):
     """Print comprehensive statistics about the simulation"""
     print("\n=== Simulation Statistics ===\n")
@@ -163,6 +213,58 @@ def print_statistics(df):
     print(f"Min: {blob_stats['malicious_votes']['min'].min()}")
     print(f"Max: {blob_stats['malicious_votes']['max'].max()}")
 
+def plot_capacity(file_path, output_dir):
+    """Plot the max sustainable blob rate per vote encoding against committee size"""
+    df = pd.read_csv(file_path)
+
+    fig, ax = plt.subplots(figsize=(15, 8))
+    for depth, group in df.groupby('Confirmation Depth'):
+        line, = ax.plot(group['Committee Size'], group['Individual Max Blob Rate'],
+                        marker='o', linewidth=2, label=f'Individual, depth {depth}')
+        ax.plot(group['Committee Size'], group['Aggregated Max Blob Rate'],
+                marker='s', linestyle='--', linewidth=2, color=line.get_color(),
+                label=f'Aggregated, depth {depth}')
+    ax.set_xscale('log', base=2)
+    ax.set_xlabel('Committee Size', fontsize=14)
+    ax.set_ylabel('Max Blobs per Block', fontsize=14)
+    ax.set_title('Capacity under the Block Size Limit', fontsize=16)
+    ax.legend(fontsize=10, ncol=2)
+    ax.grid(True, linestyle='--', alpha=0.7)
+
+    plt.tight_layout()
+    plt.savefig(os.path.join(output_dir, 'capacity.png'), dpi=300, bbox_inches='tight')
+    plt.close()
+
+def plot_fees(file_path, output_dir):
+    """Plot the base fee, blobs per block and mempool backlog over the run"""
+    df = pd.read_csv(file_path)
+
+    fig, (fee_ax, blob_ax) = plt.subplots(2, 1, figsize=(15, 10), sharex=True)
+    fee_ax.plot(df['Block'], df['Base Fee'], linewidth=2, label='Base Fee')
+    fee_ax.plot(df['Block'], df['Proposer Revenue'].rolling(window=WINDOW, min_periods=1).mean(),
+                linewidth=2, linestyle='--', label=f'Proposer Revenue ({WINDOW}-block average)')
+    fee_ax.set_ylabel('Fee', fontsize=14)
+    fee_ax.set_title('Fee Market over Time', fontsize=16)
+    fee_ax.legend()
+    fee_ax.grid(True, linestyle='--', alpha=0.7)
+
+    blob_ax.plot(df['Block'], df['Blobs Included'].rolling(window=WINDOW, min_periods=1).mean(),
+                 linewidth=2, label=f'Blobs Included ({WINDOW}-block average)')
+    blob_ax.set_ylabel('Blobs per Block', fontsize=14)
+    blob_ax.set_xlabel('Block Number', fontsize=14)
+    backlog_ax = blob_ax.twinx()
+    backlog_ax.plot(df['Block'], df['Mempool Backlog'], color='tab:red', linewidth=2,
+                    label='Mempool Backlog')
+    backlog_ax.set_ylabel('Mempool Backlog', fontsize=14)
+    lines = blob_ax.get_legend_handles_labels()
+    backlog_lines = backlog_ax.get_legend_handles_labels()
+    blob_ax.legend(lines[0] + backlog_lines[0], lines[1] + backlog_lines[1])
+    blob_ax.grid(True, linestyle='--', alpha=0.7)
+
+    plt.tight_layout()
+    plt.savefig(os.path.join(output_dir, 'fees.png'), dpi=300, bbox_inches='tight')
+    plt.close()
+
 def main():
     output_dir = 'results'
     os.makedirs(output_dir, exist_ok=True)
@@ -172,6 +274,22 @@ def main():
     plot_votes_per_block(df, output_dir)
     plot_votes_per_blob(df, output_dir)
     plot_proposer_distribution(df, output_dir)
+
+    throughput_file = 'simulation_results_throughput_data_withholding.csv'
+    if os.path.exists(throughput_file):
+        plot_throughput(throughput_file, output_dir)
+
+    bandwidth_file = 'simulation_results_bandwidth_data_withholding.csv'
+    if os.path.exists(bandwidth_file):
+        plot_bandwidth(bandwidth_file, output_dir)
+
+    capacity_file = 'simulation_results_capacity.csv'
+    if os.path.exists(capacity_file):
+        plot_capacity(capacity_file, output_dir)
+
+    fee_file = 'simulation_results_fees_fee_market.csv'
+    if os.path.exists(fee_file):
+        plot_fees(fee_file, output_dir)
     
     print_statistics(df)
 
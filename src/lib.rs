@@ -1,5 +1,15 @@
 pub mod models;
+mod scenarios;
+pub mod simulation;
 pub mod utils;
 
 // Re-export commonly used items
-pub use models::{Blob, Block, Settings};
+pub use models::{Blob, Block, Settings, SimulationResult};
+pub use scenarios::adversary::{
+    Action, Adversary, BlobView, LastMinuteWithholding, Passive, ProposerAware, PublicState,
+    ThresholdWithholding,
+};
+pub use simulation::{Scenario, Simulation, SimulationBuilder, SimulationError};
+
+// Bump whenever a change alters simulation output, so cached results are invalidated
+pub const ENGINE_VERSION: u32 = 32;
@@ -1,27 +1,471 @@
+use indicatif::ProgressBar;
+use sim::models::Settings;
+use sim::utils::adversary::create_adversary_table;
+use sim::utils::bandwidth::create_bandwidth_table;
+use sim::utils::cache::ResultCache;
+use sim::utils::congestion::{create_congestion_table, create_pool_delay_table};
+use sim::utils::das::{
+    create_das_table, create_detection_sweep_table, create_reconstruction_table,
+};
+use sim::utils::encoding::{
+    create_block_size_tables, create_capacity_csv, create_capacity_table, create_encoding_table,
+};
+use sim::utils::fees::{create_fee_censorship_table, create_fee_dynamics_table, create_fee_table};
+use sim::utils::fork::create_fork_table;
+use sim::utils::integrity::{create_integrity_table, validate_chain};
+use sim::utils::json_writer::create_results_json;
+use sim::utils::reader::{read_blobs_csv, read_blocks_csv, read_results_json};
+use sim::utils::recovery::create_recovery_table;
+use sim::utils::safety::{blocks_needed, create_safety_sweep_table, create_safety_table};
+use sim::utils::schedule::create_phase_table;
+use sim::utils::selective::{create_selective_table, create_split_vote_table};
+use sim::utils::slashing::{create_power_table, create_slashing_table};
+use sim::utils::spam::{create_spam_sweep_table, create_spam_table};
+use sim::utils::throughput::create_throughput_table;
+use sim::utils::vote_latency::{create_vote_latency_histogram, create_vote_latency_table};
+use sim::utils::vote_validation::create_vote_validation_table;
+use sim::utils::{
+    create_censorship_table, create_results_table, create_statistics_table,
+    create_voting_summary_per_blob, create_voting_summary_per_block, write_table_buffered,
+};
+use sim::{Scenario, Simulation, SimulationResult};
 use std::env;
-mod scenarios;
+use std::fs::{self, File};
+use std::io::{self, BufWriter};
+use tracing::{info, warn};
+use tracing_subscriber::EnvFilter;
+mod output;
+mod progress;
+
+const CACHE_DIR: &str = ".sim_cache";
+// File a cached sweep point keeps its result in
+const SWEEP_RESULT: &str = "result.json";
 
 fn print_usage() {
-    println!("Usage: cargo run <scenario>");
+    println!("Usage: cargo run [--quiet] <scenario>");
+    println!("       cargo run batch <scenario>...");
+    println!("       cargo run cache <ls|gc>");
+    println!("       cargo run report <results.json|results.csv>");
+    println!("       cargo run safety");
+    println!("       cargo run verify <results.json>");
+    println!("       cargo run encoding");
+    println!("       cargo run spam-sweep");
+    println!("       cargo run --features sqlite export-sqlite <results.json> <database> [run_id]");
     println!("Available scenarios:");
     println!("  basic    - Basic simulation scenario");
     println!("  vote_censorship    - Vote censorship simulation scenario");
     println!("  data_withholding    - Data withholding simulation scenario");
     println!("  smart_data_withholding    - Smart data withholding simulation scenario");
+    println!("  selective_withholding    - Selective data serving simulation scenario");
+    println!("  scheduled    - Attack phases from the `schedule` config");
+    println!("  selfish_mining    - Private branch and reorg simulation scenario");
+    println!("  equivocation    - Double voting and slashing simulation scenario");
+    println!("  congestion    - Per-block vote limit and proposer inclusion policies");
+    println!("  spam    - Junk blob submissions by malicious nodes");
+    println!("  fee_market    - Blob fees, base fee adjustment and blob censorship");
+}
+
+// Runs a scenario unless its outputs for the current settings are already cached
+fn run_cached(name: &str, cache: &ResultCache) -> bool {
+    let scenario: Scenario = match name.parse() {
+        Ok(scenario) => scenario,
+        Err(e) => {
+            println!("{}", e);
+            return false;
+        }
+    };
+    let files = output::files(scenario);
+
+    let config = Settings::load("config");
+    let key = ResultCache::key(scenario.name(), &config);
+    if let Some(key) = &key {
+        if cache.restore(key, ".").expect("Unable to read cache") {
+            info!("Cache hit for '{}' ({}), outputs restored.", name, key);
+            return true;
+        }
+    }
+
+    let progress = progress::blocks(config.total_blocks);
+    let result = Simulation::builder()
+        .settings(config.clone())
+        .scenario(scenario)
+        .on_block(|_| progress.inc(1))
+        .run()
+        .expect("Unable to run simulation");
+    progress.finish_and_clear();
+
+    output::write_outputs(&result).expect("Unable to write results");
+    info!(
+        "Simulation complete. Results written to {}.",
+        files.join(", ")
+    );
+
+    match key {
+        Some(key) => cache
+            .store(&key, name, &config, ".", &files)
+            .expect("Unable to write cache"),
+        None => warn!("No seed set in config, results for '{}' not cached.", name),
+    }
+    true
+}
+
+// Runs one point of a sweep, or loads its result when the same settings were run
+// before. Points are cached as `<scenario>_sweep` entries holding the result JSON, so
+// they never stand in for the output files of a plain run.
+fn run_sweep_point(
+    scenario: Scenario,
+    settings: &Settings,
+    cache: &ResultCache,
+    progress: &ProgressBar,
+) -> SimulationResult {
+    let run = || {
+        Simulation::builder()
+            .settings(settings.clone())
+            .scenario(scenario)
+            .on_block(|_| progress.inc(1))
+            .run()
+            .expect("Unable to run simulation")
+    };
+    let name = format!("{}_sweep", scenario.name());
+    let Some(key) = ResultCache::key(&name, settings) else {
+        warn!("No seed set in config, results for '{}' not cached.", name);
+        return run();
+    };
+
+    // The cache moves files, so the result passes through a scratch directory
+    let dir = env::temp_dir().join(format!("sim_sweep_{}", key));
+    fs::create_dir_all(&dir).expect("Unable to create sweep directory");
+    let path = dir.join(SWEEP_RESULT);
+    let result = if cache.restore(&key, &dir).expect("Unable to read cache") {
+        info!("Cache hit for '{}' ({}), result restored.", name, key);
+        progress.inc(settings.total_blocks as u64);
+        read_results_json(File::open(&path).expect("Unable to open file"))
+            .expect("Unable to read JSON")
+    } else {
+        let result = run();
+        let mut writer = BufWriter::new(File::create(&path).expect("Unable to create file"));
+        create_results_json(&result, &mut writer).expect("Unable to write results");
+        cache
+            .store(&key, &name, settings, &dir, &[SWEEP_RESULT.to_string()])
+            .expect("Unable to write cache");
+        result
+    };
+    fs::remove_dir_all(&dir).expect("Unable to remove sweep directory");
+    result
+}
+
+// Block size under individual and aggregated vote encodings for the configured
+// parameters, and the blob rate each sustains under the block size limit
+fn encoding_model() {
+    let config = Settings::load("config");
+    let mut tables = create_block_size_tables(&config);
+    tables.push(create_capacity_table(&config));
+
+    let path = "simulation_results_encoding.txt";
+    let mut stdout = BufWriter::new(io::stdout());
+    let mut writer = BufWriter::new(File::create(path).expect("Unable to create file"));
+    for table in &tables {
+        write_table_buffered(table, &mut stdout).expect("Unable to write table");
+        write_table_buffered(table, &mut writer).expect("Unable to write table");
+    }
+    let csv_path = "simulation_results_capacity.csv";
+    let mut writer = BufWriter::new(File::create(csv_path).expect("Unable to create file"));
+    create_capacity_csv(&config, &mut writer).expect("Unable to write CSV");
+    info!("Results written to {}, {}.", path, csv_path);
+}
+
+// Runs the spam scenario at each configured spam rate and tabulates what the junk
+// costs honest blobs and committees
+fn spam_sweep(cache: &ResultCache) {
+    let config = Settings::load("config");
+    let rates = &config.spam.rates;
+    let progress = progress::blocks(config.total_blocks * rates.len());
+    let mut results = Vec::new();
+    for &rate in rates {
+        let mut settings = config.clone();
+        settings.spam.rate = rate;
+        results.push(run_sweep_point(Scenario::Spam, &settings, cache, &progress));
+    }
+    progress.finish_and_clear();
+
+    let table = create_spam_sweep_table(&results);
+    let mut stdout = BufWriter::new(io::stdout());
+    write_table_buffered(&table, &mut stdout).expect("Unable to write table");
+    let path = "simulation_results_spam.txt";
+    let mut writer = BufWriter::new(File::create(path).expect("Unable to create file"));
+    write_table_buffered(&table, &mut writer).expect("Unable to write table");
+    info!("Spam sweep complete. Results written to {}.", path);
+}
+
+// Checks the header chain of a saved run, exiting with an error if it is broken
+fn verify(path: &str) {
+    let file = File::open(path).expect("Unable to open file");
+    let result = read_results_json(file).expect("Unable to read JSON");
+    let mut writer = BufWriter::new(io::stdout());
+    let table = create_integrity_table(&result.blocks);
+    write_table_buffered(&table, &mut writer).expect("Unable to write table");
+    if validate_chain(&result.blocks).is_err() {
+        std::process::exit(1);
+    }
+}
+
+// Runs selfish mining at each configured adversary power and tabulates how deep a
+// blob must be confirmed to stay below the target reversal probability
+fn safety_sweep(cache: &ResultCache) {
+    let config = Settings::load("config");
+    let levels = &config.safety.power_levels;
+    let needed = blocks_needed(config.safety.target, config.safety.max_depth);
+    let total_blocks = config.total_blocks.max(needed);
+    let progress = progress::blocks(total_blocks * levels.len());
+    let mut results = Vec::new();
+    for &power in levels {
+        let mut settings = config.clone();
+        settings.malicious_power_block = power;
+        settings.total_blocks = total_blocks;
+        // Orphaned slots leave the canonical chain shorter than the run, so the run is
+        // lengthened until the chain can resolve the target at every depth
+        let result = loop {
+            let result = run_sweep_point(Scenario::SelfishMining, &settings, cache, &progress);
+            let chain_length = result.blocks.len();
+            if chain_length >= needed || chain_length == 0 {
+                break result;
+            }
+            settings.total_blocks = settings.total_blocks * needed / chain_length + 1;
+            progress.inc_length(settings.total_blocks as u64);
+        };
+        results.push(result);
+    }
+    progress.finish_and_clear();
+
+    let table = create_safety_sweep_table(&results);
+    let mut stdout = BufWriter::new(io::stdout());
+    write_table_buffered(&table, &mut stdout).expect("Unable to write table");
+    let path = "simulation_results_safety.txt";
+    let mut writer = BufWriter::new(File::create(path).expect("Unable to create file"));
+    write_table_buffered(&table, &mut writer).expect("Unable to write table");
+    info!("Safety sweep complete. Results written to {}.", path);
+}
+
+fn cache_command(command: Option<&str>, cache: &ResultCache) {
+    match command {
+        Some("ls") => {
+            let entries = cache.entries().expect("Unable to read cache");
+            for entry in &entries {
+                println!(
+                    "{}  {:<24} seed={:<10} engine=v{}  {}",
+                    entry.key,
+                    entry.scenario,
+                    entry.seed,
+                    entry.engine_version,
+                    entry.files.join(", ")
+                );
+            }
+            println!("{} cached result(s) in '{}'.", entries.len(), CACHE_DIR);
+        }
+        Some("gc") => {
+            let removed = cache.gc().expect("Unable to clean cache");
+            println!("Removed {} stale cache entr(y/ies).", removed);
+        }
+        _ => print_usage(),
+    }
+}
+
+// Regenerates the report tables from a previously written run
+fn report(path: &str) {
+    let mut writer = BufWriter::new(io::stdout());
+
+    if path.ends_with(".csv") {
+        // CSV output carries the votes of each block but no run-wide metrics
+        let csv = std::fs::read_to_string(path).expect("Unable to open file");
+        let (blocks, honest_nodes) = read_blocks_csv(csv.as_bytes()).expect("Unable to read CSV");
+        let blobs = read_blobs_csv(csv.as_bytes()).expect("Unable to read CSV");
+        for table in [
+            create_results_table(&blocks, &honest_nodes),
+            create_voting_summary_per_block(&blocks, &honest_nodes),
+            create_voting_summary_per_blob(&blobs),
+            create_statistics_table(&blocks, &blobs, &honest_nodes),
+        ] {
+            write_table_buffered(&table, &mut writer).expect("Unable to write table");
+        }
+        return;
+    }
+
+    let file = File::open(path).expect("Unable to open file");
+    let result = read_results_json(file).expect("Unable to read JSON");
+    println!("Scenario: {}", result.scenario);
+    for table in [
+        create_results_table(&result.blocks, &result.honest_nodes),
+        create_voting_summary_per_block(&result.blocks, &result.honest_nodes),
+        create_voting_summary_per_blob(&result.blobs),
+        create_statistics_table(&result.blocks, &result.blobs, &result.honest_nodes),
+        create_throughput_table(&result.blocks, &result.blobs),
+        create_bandwidth_table(&result.blocks, &result.blobs, &result.settings),
+        create_encoding_table(&result.blocks, &result.settings),
+        create_vote_latency_table(&result.votes, &result.blobs),
+        create_vote_latency_histogram(&result.votes),
+        create_integrity_table(&result.blocks),
+    ] {
+        write_table_buffered(&table, &mut writer).expect("Unable to write table");
+    }
+    if !result.phases.is_empty() {
+        let table = create_phase_table(
+            &result.phases,
+            &result.blocks,
+            &result.blobs,
+            &result.votes,
+            &result.honest_nodes,
+        );
+        write_table_buffered(&table, &mut writer).expect("Unable to write table");
+    }
+    if let Some(metrics) = &result.fork {
+        for table in [
+            create_fork_table(metrics, result.blocks.len()),
+            create_safety_table(&result),
+        ] {
+            write_table_buffered(&table, &mut writer).expect("Unable to write table");
+        }
+    }
+    if let Some(metrics) = &result.equivocation {
+        for table in [create_slashing_table(metrics), create_power_table(metrics)] {
+            write_table_buffered(&table, &mut writer).expect("Unable to write table");
+        }
+    }
+    if let Some(metrics) = &result.congestion {
+        for table in [
+            create_congestion_table(metrics),
+            create_pool_delay_table(metrics),
+        ] {
+            write_table_buffered(&table, &mut writer).expect("Unable to write table");
+        }
+    }
+    if result.spam.is_some() {
+        let table = create_spam_table(&result);
+        write_table_buffered(&table, &mut writer).expect("Unable to write table");
+    }
+    if let Some(metrics) = &result.fees {
+        for table in [
+            create_fee_table(&result),
+            create_fee_dynamics_table(metrics),
+            create_fee_censorship_table(&result),
+        ] {
+            write_table_buffered(&table, &mut writer).expect("Unable to write table");
+        }
+    }
+    if let Some(metrics) = &result.vote_validation {
+        let table = create_vote_validation_table(metrics);
+        write_table_buffered(&table, &mut writer).expect("Unable to write table");
+    }
+    if let Some(metrics) = &result.recovery {
+        let table = create_recovery_table(Some(metrics));
+        write_table_buffered(&table, &mut writer).expect("Unable to write table");
+    }
+    if let Some(metrics) = &result.censorship {
+        let table = create_censorship_table(metrics);
+        write_table_buffered(&table, &mut writer).expect("Unable to write table");
+    }
+    if let Some(metrics) = &result.adversary {
+        let table = create_adversary_table(metrics);
+        write_table_buffered(&table, &mut writer).expect("Unable to write table");
+    }
+    if let Some(metrics) = &result.selective {
+        for table in [
+            create_selective_table(metrics),
+            create_split_vote_table(metrics),
+        ] {
+            write_table_buffered(&table, &mut writer).expect("Unable to write table");
+        }
+    }
+    if let Some(metrics) = &result.das {
+        for table in [
+            create_das_table(metrics),
+            create_detection_sweep_table(metrics.chunks),
+            create_reconstruction_table(&result.blobs, &result.votes),
+        ] {
+            write_table_buffered(&table, &mut writer).expect("Unable to write table");
+        }
+    }
+}
+
+// Adds a saved run to a SQLite database; the run id defaults to the file name
+#[cfg(feature = "sqlite")]
+fn export_sqlite(path: &str, database: &str, run_id: Option<&str>) {
+    let file = File::open(path).expect("Unable to open file");
+    let result = read_results_json(file).expect("Unable to read JSON");
+    let run_id = run_id.map(str::to_string).unwrap_or_else(|| {
+        std::path::Path::new(path)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.to_string())
+    });
+
+    let mut conn = sim::utils::sqlite::open_database(database).expect("Unable to open database");
+    sim::utils::sqlite::export_sqlite(&mut conn, &run_id, &result)
+        .expect("Unable to write database");
+    info!("Run '{}' written to '{}'.", run_id, database);
+}
+
+#[cfg(not(feature = "sqlite"))]
+fn export_sqlite(_path: &str, _database: &str, _run_id: Option<&str>) {
+    println!("SQLite export is not enabled, rebuild with `--features sqlite`.");
+}
+
+// Logs go to stderr; RUST_LOG selects levels per module (e.g. `sim::scenarios=debug`)
+fn init_logging(quiet: bool) {
+    let filter = if quiet {
+        EnvFilter::new("warn")
+    } else {
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"))
+    };
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(io::stderr)
+        .init();
 }
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
+    let mut args: Vec<String> = env::args().collect();
+    let quiet = args.iter().any(|arg| arg == "--quiet" || arg == "-q");
+    args.retain(|arg| arg != "--quiet" && arg != "-q");
+    init_logging(quiet);
+    progress::set_quiet(quiet);
+    let cache = ResultCache::open(CACHE_DIR).expect("Unable to open cache");
 
     match args.get(1).map(|s| s.as_str()) {
-        Some("basic") => scenarios::basic::run(),
-        Some("vote_censorship") => scenarios::vote_censorship::run(),
-        Some("data_withholding") => scenarios::data_withholding::run(),
-        Some("smart_data_withholding") => scenarios::smart_data_withholding::run(),
-        Some(unknown_scenario) => {
-            println!("Unknown scenario: {}", unknown_scenario);
+        Some("cache") => cache_command(args.get(2).map(|s| s.as_str()), &cache),
+        Some("report") => match args.get(2) {
+            Some(path) => report(path),
+            None => print_usage(),
+        },
+        Some("safety") => safety_sweep(&cache),
+        Some("encoding") => encoding_model(),
+        Some("spam-sweep") => spam_sweep(&cache),
+        Some("verify") => match args.get(2) {
+            Some(path) => verify(path),
+            None => print_usage(),
+        },
+        Some("export-sqlite") => match (args.get(2), args.get(3)) {
+            (Some(path), Some(database)) => {
+                export_sqlite(path, database, args.get(4).map(|s| s.as_str()))
+            }
+            _ => print_usage(),
+        },
+        Some("batch") if args.len() > 2 => {
+            for name in &args[2..] {
+                if !run_cached(name, &cache) {
+                    print_usage();
+                    return;
+                }
+            }
+        }
+        Some("batch") => {
+            println!("No scenarios specified for batch.");
             print_usage();
         }
+        Some(name) => {
+            if !run_cached(name, &cache) {
+                print_usage();
+            }
+        }
         None => {
             println!("No scenario specified.");
             print_usage();
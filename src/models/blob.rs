@@ -1,20 +1,64 @@
-#[derive(Debug, Clone)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Blob {
     pub id: usize,
     pub votes_honest: usize,
     pub votes_malicious: usize,
     pub is_confirmed: bool,
     pub proposer_status: String,
+    // Payload size in bytes
+    #[serde(default)]
+    pub size: usize,
+    // Block the blob was submitted in
+    #[serde(default)]
+    pub submitted_at: usize,
+    // Block whose proposer took the blob from the mempool; voting starts here
+    #[serde(default)]
+    pub included_at: Option<usize>,
+    // Chunks the adversary withheld, empty while the blob is fully available
+    #[serde(default)]
+    pub withheld_chunks: Vec<usize>,
+    // Whether honest nodes could jointly reconstruct the blob; unset unless it was attacked
+    #[serde(default)]
+    pub recoverable: Option<bool>,
+    // Block in which the blob reached the confirmation criterion
+    #[serde(default)]
+    pub confirmed_at: Option<usize>,
+    // Most the submitter will pay, base fee plus tip; zero outside the fee market
+    #[serde(default)]
+    pub max_fee: f64,
+    // Priority fee offered to the proposer
+    #[serde(default)]
+    pub tip: f64,
+    // Base fee plus the tip the proposer received, once included
+    #[serde(default)]
+    pub fee_paid: Option<f64>,
 }
 
 impl Blob {
-    pub fn new(id: usize) -> Self {
+    pub fn new(id: usize, submitted_at: usize, size: usize) -> Self {
         Self {
             id,
+            size,
             votes_honest: 0,
             votes_malicious: 0,
             is_confirmed: false,
             proposer_status: "honest".to_string(),
+            submitted_at,
+            included_at: None,
+            withheld_chunks: Vec::new(),
+            recoverable: None,
+            confirmed_at: None,
+            max_fee: 0.0,
+            tip: 0.0,
+            fee_paid: None,
         }
     }
+
+    // Blocks from submission to confirmation
+    pub fn confirmation_latency(&self) -> Option<usize> {
+        self.confirmed_at
+            .map(|confirmed_at| confirmed_at - self.submitted_at)
+    }
 }
@@ -1,10 +1,26 @@
+use super::BlockHeader;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Block {
     pub number: usize,
     pub proposer: usize,
+    // Blobs this block's proposer took from the mempool
+    #[serde(default)]
+    pub blobs: Vec<usize>,
     pub selected_nodes: Vec<usize>,
     pub votes: HashMap<usize, Vec<Option<bool>>>,
-    pub buffered_votes: HashMap<usize, Vec<Option<bool>>>,
+    pub buffered_votes: HashMap<usize, Vec<BufferedVote>>,
+    // Set once the block is linked into a chain; absent in runs saved before headers
+    #[serde(default)]
+    pub header: Option<BlockHeader>,
+}
+
+// A vote cast in an earlier block's committee and included in this one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BufferedVote {
+    pub voter: usize,
+    pub cast_at: usize,
+    pub decision: bool,
 }
@@ -0,0 +1,201 @@
+use super::Block;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+// Parent hash of the first block and root of an empty list of votes or blobs
+pub const ZERO_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockHeader {
+    pub parent_hash: String,
+    pub number: usize,
+    pub proposer: usize,
+    // Merkle root over the blobs the block includes, see `blob_root`
+    pub blob_root: String,
+    // Merkle root over the votes the block includes, see `vote_root`
+    pub vote_root: String,
+    // Hash over the fields above
+    pub hash: String,
+}
+
+impl BlockHeader {
+    pub fn new(parent_hash: &str, block: &Block) -> Self {
+        let blob_root = blob_root(block);
+        let vote_root = vote_root(block);
+        let hash = header_hash(
+            parent_hash,
+            block.number,
+            block.proposer,
+            &blob_root,
+            &vote_root,
+        );
+        Self {
+            parent_hash: parent_hash.to_string(),
+            number: block.number,
+            proposer: block.proposer,
+            blob_root,
+            vote_root,
+            hash,
+        }
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+pub fn header_hash(
+    parent_hash: &str,
+    number: usize,
+    proposer: usize,
+    blob_root: &str,
+    vote_root: &str,
+) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(parent_hash.as_bytes());
+    hasher.update((number as u64).to_le_bytes());
+    hasher.update((proposer as u64).to_le_bytes());
+    hasher.update(blob_root.as_bytes());
+    hasher.update(vote_root.as_bytes());
+    hex(&hasher.finalize())
+}
+
+// One leaf per included blob id, in the order the proposer took them
+pub fn blob_root(block: &Block) -> String {
+    let leaves = block
+        .blobs
+        .iter()
+        .map(|blob_id| {
+            let mut hasher = Sha256::new();
+            hasher.update([0u8]);
+            hasher.update((*blob_id as u64).to_le_bytes());
+            hasher.finalize().into()
+        })
+        .collect();
+    merkle_root(leaves)
+}
+
+// One leaf per included vote: every direct vote ordered by blob id, then committee
+// position, followed by every buffered vote ordered by blob id, then buffer order.
+// Each leaf commits to the voter and the block the vote was cast in.
+pub fn vote_root(block: &Block) -> String {
+    let leaf = |blob_id: usize, voter: usize, cast_at: usize, decision: bool| {
+        let mut hasher = Sha256::new();
+        hasher.update([0u8]);
+        hasher.update((blob_id as u64).to_le_bytes());
+        hasher.update((voter as u64).to_le_bytes());
+        hasher.update((cast_at as u64).to_le_bytes());
+        hasher.update([u8::from(decision)]);
+        hasher.finalize().into()
+    };
+
+    let mut leaves: Vec<[u8; 32]> = Vec::new();
+    let mut blob_ids: Vec<&usize> = block.votes.keys().collect();
+    blob_ids.sort_unstable();
+    for &blob_id in blob_ids {
+        for (&voter, vote) in block.selected_nodes.iter().zip(&block.votes[&blob_id]) {
+            if let Some(decision) = *vote {
+                leaves.push(leaf(blob_id, voter, block.number, decision));
+            }
+        }
+    }
+    let mut blob_ids: Vec<&usize> = block.buffered_votes.keys().collect();
+    blob_ids.sort_unstable();
+    for &blob_id in blob_ids {
+        for vote in &block.buffered_votes[&blob_id] {
+            leaves.push(leaf(blob_id, vote.voter, vote.cast_at, vote.decision));
+        }
+    }
+    merkle_root(leaves)
+}
+
+// Leaves and inner nodes are domain separated, and an odd node is carried up a level
+// unchanged
+fn merkle_root(mut leaves: Vec<[u8; 32]>) -> String {
+    if leaves.is_empty() {
+        return ZERO_HASH.to_string();
+    }
+    while leaves.len() > 1 {
+        leaves = leaves
+            .chunks(2)
+            .map(|pair| match pair {
+                [left, right] => {
+                    let mut hasher = Sha256::new();
+                    hasher.update([1u8]);
+                    hasher.update(left);
+                    hasher.update(right);
+                    hasher.finalize().into()
+                }
+                [single] => *single,
+                _ => unreachable!(),
+            })
+            .collect();
+    }
+    hex(&leaves[0])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::BufferedVote;
+    use std::collections::HashMap;
+
+    // Block 3 with committee [7, 8], where only node 7 voted Yes on blob 5
+    fn block() -> Block {
+        Block {
+            number: 3,
+            proposer: 1,
+            blobs: vec![5],
+            selected_nodes: vec![7, 8],
+            votes: HashMap::from([(5, vec![Some(true), None])]),
+            buffered_votes: HashMap::new(),
+            header: None,
+        }
+    }
+
+    #[test]
+    fn empty_roots_are_zero() {
+        let mut block = block();
+        block.blobs.clear();
+        block.votes.clear();
+        assert_eq!(blob_root(&block), ZERO_HASH);
+        assert_eq!(vote_root(&block), ZERO_HASH);
+    }
+
+    #[test]
+    fn single_leaf_is_the_root() {
+        let block = block();
+        assert_eq!(
+            blob_root(&block),
+            "c8c1a455ccb58b7dfbf235fe155c10cee23e39c630f2f8a067ad7915822bbd22"
+        );
+        assert_eq!(
+            vote_root(&block),
+            "8561774b313c19a6e71d89a907cf1dedc6ed40f8dd9b0841ac51a633f464a6c4"
+        );
+    }
+
+    #[test]
+    fn vote_root_commits_to_buffered_votes() {
+        let mut block = block();
+        block.buffered_votes.insert(
+            5,
+            vec![BufferedVote {
+                voter: 9,
+                cast_at: 2,
+                decision: false,
+            }],
+        );
+        assert_eq!(
+            vote_root(&block),
+            "fd65617da2e518ea75b3d1a8f697883f6ba18c63285f2eb431d8224e93f3b2a8"
+        );
+
+        // The same vote cast in another block is a different leaf
+        block.buffered_votes.get_mut(&5).unwrap()[0].cast_at = 1;
+        assert_ne!(
+            vote_root(&block),
+            "fd65617da2e518ea75b3d1a8f697883f6ba18c63285f2eb431d8224e93f3b2a8"
+        );
+    }
+}
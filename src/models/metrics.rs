@@ -0,0 +1,313 @@
+use super::{Behavior, InvalidVote};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+// Outcome of the pending-vote pool in the vote censorship scenario
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CensorshipMetrics {
+    // Honest votes left out by a malicious proposer
+    pub censored_votes: usize,
+    // Censored votes later included by an honest proposer
+    pub included_votes: usize,
+    // Blocks between casting and inclusion, one entry per included vote
+    pub inclusion_delays: Vec<usize>,
+    // Censored votes dropped because their blob confirmed first
+    pub lost_votes: usize,
+    // Censored votes still waiting when the run ended
+    pub pending_votes: usize,
+}
+
+impl CensorshipMetrics {
+    pub fn mean_inclusion_delay(&self) -> f64 {
+        if self.inclusion_delays.is_empty() {
+            return 0.0;
+        }
+        self.inclusion_delays.iter().sum::<usize>() as f64 / self.inclusion_delays.len() as f64
+    }
+
+    pub fn max_inclusion_delay(&self) -> usize {
+        self.inclusion_delays.iter().copied().max().unwrap_or(0)
+    }
+}
+
+// Sampling outcomes of honest committee members in the data withholding scenarios
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DasMetrics {
+    pub chunks: usize,
+    pub samples: usize,
+    pub withheld_chunks: usize,
+    // Blobs the adversary withheld chunks of
+    pub attacked_blobs: usize,
+    // Honest votes on blobs with withheld chunks
+    pub sampled_votes: usize,
+    // Of those, votes where a sample failed and the node voted No
+    pub detections: usize,
+    // Attacked blobs the honest nodes could rebuild from the chunks they sampled
+    #[serde(default)]
+    pub recoverable_blobs: usize,
+}
+
+impl DasMetrics {
+    pub fn detection_rate(&self) -> f64 {
+        if self.sampled_votes == 0 {
+            return 0.0;
+        }
+        self.detections as f64 / self.sampled_votes as f64
+    }
+}
+
+// Vote split on one blob under selective serving
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SplitVote {
+    pub blob_id: usize,
+    // Whether the adversary served the blob selectively
+    pub attacked: bool,
+    pub honest_yes: usize,
+    pub honest_no: usize,
+    // Stake behind all Yes and all No votes, malicious ones included
+    pub yes_weight: f64,
+    pub no_weight: f64,
+    pub confirmed_at: Option<usize>,
+}
+
+impl SplitVote {
+    // A confirmed blob is accepted when more stake voted Yes than No
+    pub fn accepted(&self) -> bool {
+        self.yes_weight > self.no_weight
+    }
+}
+
+// Outcome of the selective withholding scenario
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SelectiveMetrics {
+    pub serve_fraction: f64,
+    pub targeting: String,
+    // One entry per blob, by blob id
+    pub votes: Vec<SplitVote>,
+}
+
+// Decisions of the adaptive adversary, one per open blob per block
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AdversaryMetrics {
+    pub policy: String,
+    pub vote_yes: usize,
+    pub vote_no: usize,
+    pub abstain: usize,
+    pub withhold: usize,
+    pub censor: usize,
+    // Honest votes left out of blocks by censoring proposers
+    pub censored_votes: usize,
+}
+
+// A phase of the attack schedule as it played out, blocks inclusive
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PhaseRecord {
+    pub name: String,
+    pub behaviors: Vec<Behavior>,
+    pub start_block: usize,
+    pub end_block: usize,
+}
+
+// How the run recovered once its attack window ended
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RecoveryMetrics {
+    // First and last block of the attack window
+    pub attack_start: usize,
+    pub attack_end: usize,
+    // Pre-attack means; None when the attack starts with the run
+    pub baseline_honest_share: Option<f64>,
+    pub baseline_acceptance: Option<f64>,
+    // Blocks after the attack until the rolling mean is back within the band;
+    // None if it never is before the run ends
+    pub honest_share_recovery: Option<usize>,
+    pub acceptance_recovery: Option<usize>,
+    // Confirmed blobs whose final verdict disagrees with their availability
+    pub wrongly_decided: usize,
+    // Of those, blobs submitted before the attack ended
+    pub wrongly_decided_in_attack: usize,
+    // Blobs submitted but not yet confirmed when the attack ended
+    pub backlog_at_attack_end: usize,
+    // Censored votes still waiting for inclusion when the attack ended
+    pub pending_votes_at_attack_end: usize,
+}
+
+// Branches, reorgs and confirmation flips in the selfish mining scenario
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ForkMetrics {
+    pub fork_choice: String,
+    // Adversary's share of block production
+    pub malicious_power: f64,
+    // Blocks produced on any branch, one per slot
+    pub produced_blocks: usize,
+    pub adversary_blocks: usize,
+    // Adversary blocks on the final canonical chain
+    pub canonical_adversary_blocks: usize,
+    // Blocks off the final canonical chain, published or not
+    pub orphaned_blocks: usize,
+    // Canonical blocks dropped by each reorg
+    pub reorg_depths: Vec<usize>,
+    // Votes included in orphaned blocks
+    pub orphaned_votes_honest: usize,
+    pub orphaned_votes_malicious: usize,
+    // Honest votes left out of adversary blocks
+    pub censored_votes: usize,
+    // Blobs whose confirmation or verdict was undone by a reorg
+    pub flipped_blobs: Vec<usize>,
+}
+
+impl ForkMetrics {
+    pub fn max_reorg_depth(&self) -> usize {
+        self.reorg_depths.iter().copied().max().unwrap_or(0)
+    }
+
+    pub fn mean_reorg_depth(&self) -> f64 {
+        if self.reorg_depths.is_empty() {
+            return 0.0;
+        }
+        self.reorg_depths.iter().sum::<usize>() as f64 / self.reorg_depths.len() as f64
+    }
+}
+
+// Vote messages checked against the validation rules
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VoteValidationMetrics {
+    // Votes offered for inclusion that passed every rule and went into a block
+    pub accepted: usize,
+    // Votes offered for inclusion that broke a rule and were left out, such as the
+    // second vote of a detected equivocation
+    pub rejected_included: usize,
+    // Invalid votes the adversary tried, by the rule each was built to break
+    pub attempted: BTreeMap<InvalidVote, usize>,
+    // Invalid votes rejected, by the rule that caught them
+    pub rejected: BTreeMap<InvalidVote, usize>,
+}
+
+// Double votes and slashing in the equivocation scenario
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EquivocationMetrics {
+    pub penalty: String,
+    // Conflicting vote pairs sent by malicious nodes
+    pub equivocations: usize,
+    // Pairs whose second vote reached the chain through an honest proposer
+    pub detected: usize,
+    // Pairs whose second vote a malicious proposer left out
+    pub hidden: usize,
+    // Distinct nodes slashed at least once
+    pub slashed_nodes: usize,
+    pub adversary_rewards: f64,
+    pub rewards_lost: f64,
+    // Adversary share of the voting weight before the first block
+    pub initial_power: f64,
+    // Adversary share of the voting weight of nodes eligible for committees, after
+    // each block's slashing; index 0 is block 1
+    pub effective_power: Vec<f64>,
+    // Nodes slashed so far, per block
+    pub slashed_by_block: Vec<usize>,
+}
+
+impl EquivocationMetrics {
+    // First block after which the adversary's effective power is at most `fraction`
+    // of where it started
+    pub fn power_below(&self, fraction: f64) -> Option<usize> {
+        self.effective_power
+            .iter()
+            .position(|&power| power <= self.initial_power * fraction)
+            .map(|index| index + 1)
+    }
+}
+
+// Votes one kind of proposer included and left out under the block limit
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProposerInclusion {
+    pub blocks: usize,
+    // Blocks where the pool held more votes than the limit
+    pub congested_blocks: usize,
+    pub included_honest: usize,
+    pub included_malicious: usize,
+    // Votes left in the pool, counted once for every block that left them out
+    pub left_out_honest: usize,
+    pub left_out_malicious: usize,
+}
+
+impl ProposerInclusion {
+    // Share of left out votes that were honest
+    pub fn honest_left_out_share(&self) -> f64 {
+        let total = self.left_out_honest + self.left_out_malicious;
+        if total == 0 {
+            0.0
+        } else {
+            self.left_out_honest as f64 / total as f64
+        }
+    }
+}
+
+// Vote inclusion under the per-block limit in the congestion scenario
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CongestionMetrics {
+    pub honest_policy: String,
+    pub malicious_policy: String,
+    pub honest_proposers: ProposerInclusion,
+    pub malicious_proposers: ProposerInclusion,
+    // Blocks between casting and inclusion, one entry per included vote, by voter
+    pub honest_delays: Vec<usize>,
+    pub malicious_delays: Vec<usize>,
+    // Votes dropped because their blob confirmed first
+    pub lost_honest: usize,
+    pub lost_malicious: usize,
+    // Votes still in the pool when the run ended
+    pub pending_votes: usize,
+}
+
+// Junk blob submissions in the spam scenario
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SpamMetrics {
+    // Junk blobs that reached the mempool
+    pub submitted: usize,
+    // Junk blobs the adversary could no longer pay the fee for
+    pub refused_budget: usize,
+    // Junk blobs refused because the submitter hit the rate limit
+    pub refused_rate_limit: usize,
+    // Submitted junk blobs whose data was never served
+    pub unavailable: usize,
+    pub fees_paid: f64,
+}
+
+// Fee market state after one block
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FeeRecord {
+    // Base fee the block's blobs paid
+    pub base_fee: f64,
+    pub included: usize,
+    // Blobs left in the mempool after the block
+    pub backlog: usize,
+    // Tips the proposer received
+    pub revenue: f64,
+    // Base fees paid, which go to no one
+    pub burned: f64,
+    pub proposer_honest: bool,
+}
+
+// Fees and blob censorship in the fee market scenario
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FeeMetrics {
+    pub policy: String,
+    // One record per block; index 0 is block 1
+    pub blocks: Vec<FeeRecord>,
+    // Blobs malicious proposers refuse to include
+    pub censored_blobs: Vec<usize>,
+    // Tips malicious proposers gave up by skipping censored blobs
+    pub forgone_revenue: f64,
+    // Base fee the next block would charge
+    pub final_base_fee: f64,
+}
+
+impl FeeMetrics {
+    // Total tips received by honest or malicious proposers
+    pub fn revenue(&self, proposer_honest: bool) -> f64 {
+        self.blocks
+            .iter()
+            .filter(|record| record.proposer_honest == proposer_honest)
+            .map(|record| record.revenue)
+            .fold(0.0, |sum, revenue| sum + revenue)
+    }
+}
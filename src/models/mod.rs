@@ -1,7 +1,27 @@
 mod blob;
 mod block;
+mod header;
+mod metrics;
+mod result;
 mod settings;
+mod vote_message;
+mod vote_record;
 
 pub use blob::Blob;
-pub use block::Block;
-pub use settings::Settings;
+pub use block::{Block, BufferedVote};
+pub use header::{blob_root, header_hash, vote_root, BlockHeader, ZERO_HASH};
+pub use metrics::{
+    AdversaryMetrics, CensorshipMetrics, CongestionMetrics, DasMetrics, EquivocationMetrics,
+    FeeMetrics, FeeRecord, ForkMetrics, PhaseRecord, ProposerInclusion, RecoveryMetrics,
+    SelectiveMetrics, SpamMetrics, SplitVote, VoteValidationMetrics,
+};
+pub use result::SimulationResult;
+pub use settings::{
+    AdversaryPolicy, AdversarySettings, AttackPhase, BandwidthSettings, Behavior, BlobArrival,
+    BlobSize, BlockLimitSettings, DasLayout, DasSettings, EncodingSettings, EquivocationSettings,
+    FeeMarketSettings, FeePolicy, FeeRange, ForkChoice, InclusionPolicy, InvalidVoteSettings,
+    RateLimit, RecoverySettings, SafetySettings, SelectiveSettings, Settings, SlashingPenalty,
+    SpamSettings, StakeDistribution, StartCondition, Targeting,
+};
+pub use vote_message::{InvalidVote, VoteMessage};
+pub use vote_record::VoteRecord;
@@ -0,0 +1,69 @@
+use super::{
+    AdversaryMetrics, Blob, Block, CensorshipMetrics, CongestionMetrics, DasMetrics,
+    EquivocationMetrics, FeeMetrics, ForkMetrics, PhaseRecord, RecoveryMetrics, SelectiveMetrics,
+    Settings, SpamMetrics, VoteRecord, VoteValidationMetrics,
+};
+use crate::simulation::Scenario;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+// Everything a run produces, in a form that can be written out and loaded back
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimulationResult {
+    pub scenario: Scenario,
+    pub settings: Settings,
+    pub honest_nodes: HashSet<usize>,
+    pub blocks: Vec<Block>,
+    pub blobs: HashMap<usize, Blob>,
+    #[serde(default)]
+    pub votes: Vec<VoteRecord>,
+    // Only set by the vote censorship and scheduled scenarios
+    #[serde(default)]
+    pub censorship: Option<CensorshipMetrics>,
+    // Only set by the data withholding and scheduled scenarios
+    #[serde(default)]
+    pub das: Option<DasMetrics>,
+    // Only set by the selective withholding scenario
+    #[serde(default)]
+    pub selective: Option<SelectiveMetrics>,
+    // Only set by the smart data withholding and scheduled scenarios
+    #[serde(default)]
+    pub adversary: Option<AdversaryMetrics>,
+    // Only set by the selfish mining scenario
+    #[serde(default)]
+    pub fork: Option<ForkMetrics>,
+    // Only set by the equivocation scenario
+    #[serde(default)]
+    pub equivocation: Option<EquivocationMetrics>,
+    // Only set by the congestion scenario
+    #[serde(default)]
+    pub congestion: Option<CongestionMetrics>,
+    // Only set by the spam scenario
+    #[serde(default)]
+    pub spam: Option<SpamMetrics>,
+    // Only set by the fee market scenario
+    #[serde(default)]
+    pub fees: Option<FeeMetrics>,
+    // Attack phases in block order; empty for scenarios without an attack window
+    #[serde(default)]
+    pub phases: Vec<PhaseRecord>,
+    // Set after the run whenever there is an attack window
+    #[serde(default)]
+    pub recovery: Option<RecoveryMetrics>,
+    // Set after every run; absent in runs saved before vote validation
+    #[serde(default)]
+    pub vote_validation: Option<VoteValidationMetrics>,
+}
+
+impl SimulationResult {
+    // First and last block of any phase with adversary behaviors
+    pub fn attack_window(&self) -> Option<(usize, usize)> {
+        let mut attacks = self
+            .phases
+            .iter()
+            .filter(|phase| !phase.behaviors.is_empty());
+        let first = attacks.next()?;
+        let last = attacks.next_back().unwrap_or(first);
+        Some((first.start_block, last.end_block))
+    }
+}
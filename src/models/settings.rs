@@ -1,6 +1,7 @@
-use serde::Deserialize;
+use super::InvalidVote;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Settings {
     pub total_nodes: usize,
     pub malicious_nodes: usize,
@@ -10,6 +11,659 @@ pub struct Settings {
     pub total_blocks: usize,
     pub reliable_nodes: usize,
     pub k_f: usize,
+    // Malicious proposers censor honest votes in blocks before this one; unset means the whole run
+    #[serde(default)]
+    pub censorship_end_block: Option<usize>,
+    // How blobs arrive in the mempool; one per block by default
+    #[serde(default)]
+    pub blob_arrival: BlobArrival,
+    // Blobs a proposer may take from the mempool per block; unset means all of them
+    #[serde(default)]
+    pub max_blobs_per_block: Option<usize>,
+    // Message sizes and bandwidth caps for the bandwidth report
+    #[serde(default)]
+    pub bandwidth: BandwidthSettings,
+    // Erasure coding and sampling used by the data withholding scenarios
+    #[serde(default)]
+    pub das: DasSettings,
+    // Stake weighting each node's vote; equal by default
+    #[serde(default)]
+    pub stake: StakeDistribution,
+    // Selective-serving adversary in the selective withholding scenario
+    #[serde(default)]
+    pub selective: SelectiveSettings,
+    // Adaptive adversary in the smart data withholding scenario
+    #[serde(default)]
+    pub adversary: AdversarySettings,
+    // Ordered attack phases applied by the scheduled scenario
+    #[serde(default)]
+    pub schedule: Vec<AttackPhase>,
+    // How honest nodes pick between competing branches in the selfish mining scenario
+    #[serde(default)]
+    pub fork_choice: ForkChoice,
+    // Power levels and failure target for the confirmation depth safety analysis
+    #[serde(default)]
+    pub safety: SafetySettings,
+    // Baseline band used to tell when the run has recovered from an attack
+    #[serde(default)]
+    pub recovery: RecoverySettings,
+    // Invalid vote messages the adversary sends, all rejected by validation
+    #[serde(default)]
+    pub invalid_votes: InvalidVoteSettings,
+    // Double voting and the slashing penalty in the equivocation scenario
+    #[serde(default)]
+    pub equivocation: EquivocationSettings,
+    // Per-block vote limit and proposer inclusion policies in the congestion scenario
+    #[serde(default)]
+    pub block_limit: BlockLimitSettings,
+    // Junk blob submissions and their countermeasures in the spam scenario
+    #[serde(default)]
+    pub spam: SpamSettings,
+    // Blob fees, base fee adjustment and blob censorship in the fee market scenario
+    #[serde(default)]
+    pub fee_market: FeeMarketSettings,
+    // Fixed RNG seed; without one a random seed is drawn and the run is never cached
+    #[serde(default)]
+    pub seed: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum BlobArrival {
+    // `rate` blobs per block, fractional rates spread evenly over blocks
+    Fixed { rate: f64 },
+    // Poisson-distributed arrivals with mean `rate` per block
+    Poisson { rate: f64 },
+    // Arrivals per block taken from `counts`, repeated once exhausted
+    Trace { counts: Vec<usize> },
+}
+
+impl Default for BlobArrival {
+    fn default() -> Self {
+        BlobArrival::Fixed { rate: 1.0 }
+    }
+}
+
+impl BlobArrival {
+    // Long-run mean of blobs arriving per block
+    pub fn mean(&self) -> f64 {
+        match self {
+            BlobArrival::Fixed { rate } | BlobArrival::Poisson { rate } => rate.max(0.0),
+            BlobArrival::Trace { counts } if !counts.is_empty() => {
+                counts.iter().sum::<usize>() as f64 / counts.len() as f64
+            }
+            BlobArrival::Trace { .. } => 0.0,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct BandwidthSettings {
+    // Size of each submitted blob
+    pub blob_size: BlobSize,
+    // Wire size of a single vote message
+    pub vote_bytes: usize,
+    // Fixed per-block overhead: header, proposer signature and blob commitments
+    pub header_bytes: usize,
+    // Warn when a single node moves more than this many bytes in one block
+    pub node_cap_bytes: Option<u64>,
+    // Warn when the whole network moves more than this many bytes in one block
+    pub network_cap_bytes: Option<u64>,
+    // Vote encodings compared by the encoding cost model
+    pub encoding: EncodingSettings,
+}
+
+impl Default for BandwidthSettings {
+    fn default() -> Self {
+        Self {
+            blob_size: BlobSize::default(),
+            vote_bytes: 128,
+            header_bytes: 512,
+            node_cap_bytes: None,
+            network_cap_bytes: None,
+            encoding: EncodingSettings::default(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct EncodingSettings {
+    // Aggregate BLS signature over one blob's votes
+    pub signature_bytes: usize,
+    // Blob reference in an aggregated vote entry
+    pub blob_id_bytes: usize,
+    // Block size limit for the capacity chart
+    pub block_size_limit: usize,
+    // Committee sizes swept by the cost model
+    pub committee_sizes: Vec<usize>,
+    // Confirmation depths swept by the cost model
+    pub depths: Vec<usize>,
+    // Unconfirmed blob counts swept by the cost model
+    pub open_blobs: Vec<usize>,
+}
+
+impl Default for EncodingSettings {
+    fn default() -> Self {
+        Self {
+            signature_bytes: 96,
+            blob_id_bytes: 8,
+            block_size_limit: 2_097_152,
+            committee_sizes: vec![16, 32, 64, 128, 256, 512, 1024],
+            depths: vec![1, 5, 10, 20, 40, 80],
+            open_blobs: vec![1, 10, 50, 100, 200, 500],
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum BlobSize {
+    Fixed { bytes: usize },
+    // Uniform over `min..=max`
+    Uniform { min: usize, max: usize },
+    // Log-normal with the given mean and standard deviation in bytes, for heavy-tailed payloads
+    LogNormal { mean: f64, std_dev: f64 },
+}
+
+impl Default for BlobSize {
+    fn default() -> Self {
+        // 128 KiB, the EIP-4844 blob size
+        BlobSize::Fixed { bytes: 131_072 }
+    }
+}
+
+impl BlobSize {
+    pub fn mean(&self) -> f64 {
+        match *self {
+            BlobSize::Fixed { bytes } => bytes as f64,
+            BlobSize::Uniform { min, max } => (min + max) as f64 / 2.0,
+            BlobSize::LogNormal { mean, .. } => mean,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct DasSettings {
+    // Chunk layout; `chunks` and `data_chunks` only apply to the one-dimensional code
+    pub layout: DasLayout,
+    // Chunks each blob is erasure-coded into
+    pub chunks: usize,
+    // Chunks needed to reconstruct the blob
+    pub data_chunks: usize,
+    // Distinct chunks each committee member samples before voting
+    pub samples: usize,
+    // Chunks the adversary withholds from an attacked blob; unset means the fewest
+    // that make the blob unrecoverable
+    pub withheld_chunks: Option<usize>,
+}
+
+impl Default for DasSettings {
+    fn default() -> Self {
+        Self {
+            layout: DasLayout::OneD,
+            chunks: 64,
+            data_chunks: 32,
+            samples: 8,
+            withheld_chunks: None,
+        }
+    }
+}
+
+impl DasSettings {
+    pub fn total_chunks(&self) -> usize {
+        match self.layout {
+            DasLayout::OneD => self.chunks,
+            DasLayout::TwoD { side } => side * side,
+        }
+    }
+
+    pub fn withheld_chunks(&self) -> usize {
+        let unrecoverable = match self.layout {
+            DasLayout::OneD => (self.chunks + 1).saturating_sub(self.data_chunks),
+            // A (side/2 + 1)^2 square is the smallest pattern that blocks reconstruction
+            DasLayout::TwoD { side } => (side / 2 + 1).pow(2),
+        };
+        self.withheld_chunks
+            .unwrap_or(unrecoverable)
+            .min(self.total_chunks())
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DasLayout {
+    // Reed-Solomon over a single row of chunks
+    OneD,
+    // `side` x `side` extended grid; any half of a row or column reconstructs it
+    TwoD { side: usize },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum StakeDistribution {
+    #[default]
+    Equal,
+    // Uniform over `min..max`
+    Uniform {
+        min: f64,
+        max: f64,
+    },
+    // Heavy-tailed stake, a few large holders and many small ones
+    Pareto {
+        scale: f64,
+        shape: f64,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct SelectiveSettings {
+    // Share of honest nodes the adversary serves an attacked blob to
+    pub serve_fraction: f64,
+    // Which honest nodes are served first
+    pub targeting: Targeting,
+}
+
+impl Default for SelectiveSettings {
+    fn default() -> Self {
+        Self {
+            serve_fraction: 0.5,
+            targeting: Targeting::Random,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Targeting {
+    #[default]
+    Random,
+    // Serve the nodes with the least stake, so Yes votes carry as little weight as possible
+    LowestWeight,
+    // Serve the nodes that already voted on the blob, keeping their view consistent
+    PreviousVoters,
+}
+
+impl Targeting {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Targeting::Random => "random",
+            Targeting::LowestWeight => "lowest_weight",
+            Targeting::PreviousVoters => "previous_voters",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct AdversarySettings {
+    pub policy: AdversaryPolicy,
+    // Honest votes on a blob before the adversary turns against it
+    pub threshold: usize,
+    // Blocks before confirmation in which the adversary acts
+    pub horizon: usize,
+    // Whether the adversary knows the next block's proposer in advance
+    pub predictable_proposer: bool,
+}
+
+impl Default for AdversarySettings {
+    fn default() -> Self {
+        Self {
+            policy: AdversaryPolicy::Threshold,
+            threshold: 2,
+            horizon: 5,
+            predictable_proposer: false,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AdversaryPolicy {
+    Passive,
+    Threshold,
+    LastMinute,
+    ProposerAware,
+}
+
+// One phase of an attack schedule. A phase starts at block `start`, once `start_when`
+// holds, or when the previous phase's `blocks` run out, whichever comes first.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct AttackPhase {
+    pub name: String,
+    // Adversary behaviors active during the phase; none means malicious nodes just vote No
+    #[serde(default)]
+    pub behaviors: Vec<Behavior>,
+    #[serde(default)]
+    pub start: Option<usize>,
+    #[serde(default)]
+    pub start_when: Option<StartCondition>,
+    // Length of the phase; unset means until the next phase starts or the run ends
+    #[serde(default)]
+    pub blocks: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum Behavior {
+    // Malicious proposers leave honest votes out of their blocks
+    Censor,
+    // Chunks of every open blob are withheld and malicious nodes vote Yes
+    Withhold,
+    // The adversary from `[adversary]` decides per blob
+    Adaptive,
+}
+
+impl Behavior {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Behavior::Censor => "censor",
+            Behavior::Withhold => "withhold",
+            Behavior::Adaptive => "adaptive",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum StartCondition {
+    // More than `blobs` blobs are included but not yet confirmed
+    BacklogAbove { blobs: usize },
+    // The block's proposer is malicious
+    MaliciousProposer,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ForkChoice {
+    // The highest branch wins
+    #[default]
+    Longest,
+    // The branch with the most included votes wins, then the highest
+    MostVotes,
+}
+
+impl ForkChoice {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ForkChoice::Longest => "longest",
+            ForkChoice::MostVotes => "most_votes",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct RecoverySettings {
+    // Largest distance from the pre-attack mean, as a fraction, still counted as recovered
+    pub band: f64,
+    // Blocks a rolling mean must stay within the band for
+    pub window: usize,
+}
+
+impl Default for RecoverySettings {
+    fn default() -> Self {
+        Self {
+            band: 0.05,
+            window: 10,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct SafetySettings {
+    // Acceptable probability that a confirmed blob is later reverted
+    pub target: f64,
+    // Adversary block production shares to sweep, in percent
+    pub power_levels: Vec<usize>,
+    // Deepest confirmation depth considered
+    pub max_depth: usize,
+}
+
+impl Default for SafetySettings {
+    fn default() -> Self {
+        Self {
+            target: 0.001,
+            power_levels: vec![10, 20, 25, 30, 33, 40],
+            max_depth: 100,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct InvalidVoteSettings {
+    // Invalid vote messages tried per block
+    pub per_block: usize,
+    // Rules the attempts are built to break, picked at random per attempt
+    pub kinds: Vec<InvalidVote>,
+}
+
+impl Default for InvalidVoteSettings {
+    fn default() -> Self {
+        Self {
+            per_block: 0,
+            kinds: InvalidVote::ALL.to_vec(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct EquivocationSettings {
+    // Chance that a malicious committee member double votes on each open blob
+    pub rate: f64,
+    pub penalty: SlashingPenalty,
+    // Reward per counted vote
+    pub vote_reward: f64,
+}
+
+impl Default for EquivocationSettings {
+    fn default() -> Self {
+        Self {
+            rate: 0.5,
+            penalty: SlashingPenalty::default(),
+            vote_reward: 1.0,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct BlockLimitSettings {
+    // Votes a block can carry
+    pub max_votes: Option<usize>,
+    // Block size in bytes; votes get what the header and new blob payloads leave
+    pub max_bytes: Option<usize>,
+    // Which votes honest proposers include first when the limit binds
+    pub honest_policy: InclusionPolicy,
+    // Which votes malicious proposers include first when the limit binds
+    pub malicious_policy: InclusionPolicy,
+}
+
+impl Default for BlockLimitSettings {
+    fn default() -> Self {
+        Self {
+            max_votes: None,
+            max_bytes: None,
+            honest_policy: InclusionPolicy::Fifo,
+            malicious_policy: InclusionPolicy::Adversarial,
+        }
+    }
+}
+
+impl BlockLimitSettings {
+    // Votes that fit next to the header and `payload_bytes` of new blobs
+    pub fn vote_capacity(&self, payload_bytes: usize, bandwidth: &BandwidthSettings) -> usize {
+        let by_bytes = self.max_bytes.map(|max_bytes| {
+            max_bytes.saturating_sub(bandwidth.header_bytes + payload_bytes)
+                / bandwidth.vote_bytes.max(1)
+        });
+        match (self.max_votes, by_bytes) {
+            (Some(votes), Some(bytes)) => votes.min(bytes),
+            (votes, bytes) => votes.or(bytes).unwrap_or(usize::MAX),
+        }
+    }
+}
+
+// Order in which a proposer takes votes from the pool
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum InclusionPolicy {
+    // Oldest votes first
+    Fifo,
+    // Every vote for the earliest included blob before any for the next
+    OldestBlobFirst,
+    // Votes for blobs whose Yes and No tallies are closest first
+    ClosestToThreshold,
+    // Malicious votes first, honest votes only with room to spare
+    Adversarial,
+}
+
+impl InclusionPolicy {
+    pub fn name(&self) -> &'static str {
+        match self {
+            InclusionPolicy::Fifo => "fifo",
+            InclusionPolicy::OldestBlobFirst => "oldest_blob_first",
+            InclusionPolicy::ClosestToThreshold => "closest_to_threshold",
+            InclusionPolicy::Adversarial => "adversarial",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct SpamSettings {
+    // Mean junk blobs malicious nodes submit per block, Poisson distributed
+    pub rate: f64,
+    // Payload bytes of a junk blob
+    pub size: usize,
+    // Share of junk blobs whose data is never served
+    pub unavailable: f64,
+    // Fee paid for every submitted blob
+    pub fee: f64,
+    // Total fees the adversary can pay; unset means unlimited
+    pub budget: Option<f64>,
+    // Submissions allowed per node; unset means unlimited
+    pub rate_limit: Option<RateLimit>,
+    // Spam rates swept by `cargo run spam-sweep`
+    pub rates: Vec<f64>,
+}
+
+impl Default for SpamSettings {
+    fn default() -> Self {
+        Self {
+            rate: 2.0,
+            size: 1024,
+            unavailable: 0.5,
+            fee: 0.0,
+            budget: None,
+            rate_limit: None,
+            rates: vec![0.0, 0.5, 1.0, 2.0, 5.0, 10.0],
+        }
+    }
+}
+
+// At most `blobs` submissions per node in any `window` consecutive blocks
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct RateLimit {
+    pub blobs: usize,
+    pub window: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct FeeMarketSettings {
+    // Blobs per block the base fee steers towards; blocks hold twice this unless
+    // `max_blobs_per_block` is set
+    pub target: usize,
+    pub initial_base_fee: f64,
+    pub min_base_fee: f64,
+    // Relative base fee change after a block with no blobs or with twice the target
+    pub max_change: f64,
+    // Most a submitter will pay per blob, drawn uniformly per blob
+    pub max_fee: FeeRange,
+    // Priority fee a submitter offers the proposer, drawn uniformly per blob
+    pub tip: FeeRange,
+    // How every proposer picks blobs from the mempool
+    pub policy: FeePolicy,
+    // Share of blobs malicious proposers refuse to include
+    pub censored_share: f64,
+}
+
+impl Default for FeeMarketSettings {
+    fn default() -> Self {
+        Self {
+            target: 3,
+            initial_base_fee: 1.0,
+            min_base_fee: 0.01,
+            max_change: 0.125,
+            max_fee: FeeRange {
+                min: 1.0,
+                max: 10.0,
+            },
+            tip: FeeRange { min: 0.1, max: 2.0 },
+            policy: FeePolicy::MaxRevenue,
+            censored_share: 0.1,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct FeeRange {
+    pub min: f64,
+    pub max: f64,
+}
+
+// Order in which a proposer takes blobs whose max fee covers the base fee
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FeePolicy {
+    // Oldest blobs first
+    Fifo,
+    // Highest tips first, which maximizes the proposer's revenue
+    MaxRevenue,
+}
+
+impl FeePolicy {
+    pub fn name(&self) -> &'static str {
+        match self {
+            FeePolicy::Fifo => "fifo",
+            FeePolicy::MaxRevenue => "max_revenue",
+        }
+    }
+}
+
+// Applied to a node each time its equivocation is detected
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SlashingPenalty {
+    // The node is never selected for a committee again
+    #[default]
+    Removal,
+    // The node's weight, and with it its chance of committee selection, is
+    // multiplied by `factor`
+    WeightReduction {
+        factor: f64,
+    },
+    // The node forfeits `fraction` of the rewards it has earned
+    RewardLoss {
+        fraction: f64,
+    },
+}
+
+impl SlashingPenalty {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SlashingPenalty::Removal => "removal",
+            SlashingPenalty::WeightReduction { .. } => "weight_reduction",
+            SlashingPenalty::RewardLoss { .. } => "reward_loss",
+        }
+    }
 }
 
 impl Settings {
@@ -22,3 +676,27 @@ impl Settings {
             .unwrap()
     }
 }
+
+#[cfg(test)]
+impl Settings {
+    // A small seeded network with every optional section at its default, for unit tests
+    pub(crate) fn for_tests() -> Self {
+        let toml = r#"
+            total_nodes = 20
+            malicious_nodes = 5
+            nodes_per_block = 4
+            confirmation_depth = 3
+            malicious_power_block = 30
+            total_blocks = 20
+            reliable_nodes = 10
+            k_f = 2
+            seed = 7
+        "#;
+        config::Config::builder()
+            .add_source(config::File::from_str(toml, config::FileFormat::Toml))
+            .build()
+            .unwrap()
+            .try_deserialize()
+            .unwrap()
+    }
+}
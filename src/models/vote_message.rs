@@ -0,0 +1,97 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+// A signed vote as it travels to the proposer. The signature is a mock scheme: each
+// node's key is derived from its id, so anyone can check it, but only messages built
+// through `sign` for the claimed voter verify.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VoteMessage {
+    pub voter: usize,
+    pub blob_id: usize,
+    // Block the vote was cast in
+    pub block: usize,
+    pub decision: bool,
+    pub signature: String,
+}
+
+// Why a vote message was rejected
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum InvalidVote {
+    // The signature does not match the voter and payload
+    BadSignature,
+    // The voter is not in the block's committee
+    NotInCommittee,
+    // The voter already voted on the blob in this committee round
+    Duplicate,
+    // The blob does not exist or was not submitted yet
+    UnknownBlob,
+    // The blob confirmed before the vote
+    ExpiredBlob,
+}
+
+impl InvalidVote {
+    pub const ALL: [InvalidVote; 5] = [
+        InvalidVote::BadSignature,
+        InvalidVote::NotInCommittee,
+        InvalidVote::Duplicate,
+        InvalidVote::UnknownBlob,
+        InvalidVote::ExpiredBlob,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            InvalidVote::BadSignature => "bad_signature",
+            InvalidVote::NotInCommittee => "not_in_committee",
+            InvalidVote::Duplicate => "duplicate",
+            InvalidVote::UnknownBlob => "unknown_blob",
+            InvalidVote::ExpiredBlob => "expired_blob",
+        }
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn node_key(voter: usize) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(b"sim-node-key");
+    hasher.update((voter as u64).to_le_bytes());
+    hasher.finalize().into()
+}
+
+fn signature(key: &[u8; 32], payload: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(key);
+    hasher.update(payload);
+    hex(&hasher.finalize())
+}
+
+impl VoteMessage {
+    pub fn sign(voter: usize, blob_id: usize, block: usize, decision: bool) -> Self {
+        let mut message = Self {
+            voter,
+            blob_id,
+            block,
+            decision,
+            signature: String::new(),
+        };
+        message.signature = signature(&node_key(voter), &message.payload());
+        message
+    }
+
+    // Signed bytes: a domain tag, then the fields in declaration order
+    pub fn payload(&self) -> Vec<u8> {
+        let mut payload = b"sim-vote".to_vec();
+        payload.extend((self.voter as u64).to_le_bytes());
+        payload.extend((self.blob_id as u64).to_le_bytes());
+        payload.extend((self.block as u64).to_le_bytes());
+        payload.push(u8::from(self.decision));
+        payload
+    }
+
+    pub fn verify(&self) -> bool {
+        self.signature == signature(&node_key(self.voter), &self.payload())
+    }
+}
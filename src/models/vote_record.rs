@@ -0,0 +1,83 @@
+use serde::{Deserialize, Serialize};
+
+// One vote from cast to inclusion on chain
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VoteRecord {
+    pub voter: usize,
+    pub blob_id: usize,
+    pub decision: bool,
+    pub voter_honest: bool,
+    pub cast_at: usize,
+    // Honesty of the proposer of the block the vote was cast in
+    pub cast_proposer_honest: bool,
+    // None while censored; stays None if the blob confirmed before inclusion
+    pub included_at: Option<usize>,
+    // The second, conflicting vote of an equivocation, which never counts
+    #[serde(default)]
+    pub equivocation: bool,
+}
+
+impl VoteRecord {
+    // A vote that lands in the block it was cast in
+    pub fn included(
+        voter: usize,
+        blob_id: usize,
+        decision: bool,
+        voter_honest: bool,
+        block: usize,
+        proposer_honest: bool,
+    ) -> Self {
+        Self {
+            voter,
+            blob_id,
+            decision,
+            voter_honest,
+            cast_at: block,
+            cast_proposer_honest: proposer_honest,
+            included_at: Some(block),
+            equivocation: false,
+        }
+    }
+
+    // A vote left out of the block it was cast in
+    pub fn censored(
+        voter: usize,
+        blob_id: usize,
+        decision: bool,
+        voter_honest: bool,
+        block: usize,
+        proposer_honest: bool,
+    ) -> Self {
+        Self {
+            included_at: None,
+            ..Self::included(
+                voter,
+                blob_id,
+                decision,
+                voter_honest,
+                block,
+                proposer_honest,
+            )
+        }
+    }
+
+    // A malicious voter's second vote on a blob it already voted on in `block`
+    pub fn equivocation(
+        voter: usize,
+        blob_id: usize,
+        decision: bool,
+        block: usize,
+        proposer_honest: bool,
+    ) -> Self {
+        Self {
+            equivocation: true,
+            ..Self::censored(voter, blob_id, decision, false, block, proposer_honest)
+        }
+    }
+
+    // Blocks from casting to inclusion
+    pub fn latency(&self) -> Option<usize> {
+        self.included_at
+            .map(|included_at| included_at - self.cast_at)
+    }
+}
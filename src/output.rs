@@ -0,0 +1,332 @@
+use sim::utils::adversary::create_adversary_table;
+use sim::utils::bandwidth::{create_bandwidth_csv, create_bandwidth_table};
+use sim::utils::congestion::{create_congestion_table, create_pool_delay_table};
+use sim::utils::csv_writer::create_results_csv;
+use sim::utils::das::{
+    create_das_table, create_detection_sweep_table, create_reconstruction_table,
+};
+use sim::utils::encoding::create_encoding_table;
+use sim::utils::fees::{
+    create_fee_censorship_table, create_fee_csv, create_fee_dynamics_table, create_fee_table,
+};
+use sim::utils::fork::create_fork_table;
+use sim::utils::json_writer::create_results_json;
+use sim::utils::recovery::create_recovery_table;
+use sim::utils::safety::create_safety_table;
+use sim::utils::schedule::create_phase_table;
+use sim::utils::selective::{create_selective_table, create_split_vote_table};
+use sim::utils::slashing::{create_power_table, create_slashing_table};
+use sim::utils::spam::create_spam_table;
+use sim::utils::throughput::{create_throughput_csv, create_throughput_table};
+use sim::utils::vote_latency::{create_vote_latency_histogram, create_vote_latency_table};
+use sim::utils::vote_validation::create_vote_validation_table;
+use sim::utils::{
+    create_censorship_table, create_statistics_table, create_voting_summary_per_blob,
+    create_voting_summary_per_block, write_table_buffered,
+};
+use sim::{Scenario, SimulationResult};
+use std::fs::File;
+use std::io::{self, BufWriter};
+
+#[derive(Debug, Clone, Copy)]
+pub enum OutputKind {
+    ResultsCsv,
+    ResultsJson,
+    Statistics,
+    PerBlockTable,
+    PerBlobTable,
+    CensorshipTable,
+    VoteLatencyTable,
+    ThroughputCsv,
+    BandwidthCsv,
+    DasTable,
+    SelectiveTable,
+    AdversaryTable,
+    PhaseTable,
+    RecoveryTable,
+    ForkTable,
+    VoteValidationTable,
+    SlashingTable,
+    CongestionTable,
+    SpamTable,
+    FeeTable,
+    FeeCsv,
+}
+
+fn suffix(scenario: Scenario) -> &'static str {
+    match scenario {
+        Scenario::Basic => "basic",
+        Scenario::VoteCensorship => "vc",
+        Scenario::DataWithholding => "data_withholding",
+        Scenario::SmartDataWithholding => "smart_data_withholding",
+        Scenario::SelectiveWithholding => "selective_withholding",
+        Scenario::Scheduled => "scheduled",
+        Scenario::SelfishMining => "selfish_mining",
+        Scenario::Equivocation => "equivocation",
+        Scenario::Congestion => "congestion",
+        Scenario::Spam => "spam",
+        Scenario::FeeMarket => "fee_market",
+    }
+}
+
+// Files written for each scenario, relative to the working directory
+pub fn outputs(scenario: Scenario) -> Vec<(String, OutputKind)> {
+    let suffix = suffix(scenario);
+    let mut outputs = vec![
+        (
+            format!("simulation_results_{}.csv", suffix),
+            OutputKind::ResultsCsv,
+        ),
+        (
+            format!("simulation_results_{}.json", suffix),
+            OutputKind::ResultsJson,
+        ),
+        (
+            format!("simulation_results_stats_{}.txt", suffix),
+            OutputKind::Statistics,
+        ),
+        (
+            format!("simulation_results_vote_latency_{}.txt", suffix),
+            OutputKind::VoteLatencyTable,
+        ),
+        (
+            format!("simulation_results_throughput_{}.csv", suffix),
+            OutputKind::ThroughputCsv,
+        ),
+        (
+            format!("simulation_results_bandwidth_{}.csv", suffix),
+            OutputKind::BandwidthCsv,
+        ),
+    ];
+
+    if matches!(scenario, Scenario::VoteCensorship | Scenario::Scheduled) {
+        outputs.extend([
+            (
+                format!("simulation_results_per_block_{}.txt", suffix),
+                OutputKind::PerBlockTable,
+            ),
+            (
+                format!("simulation_results_per_blob_{}.txt", suffix),
+                OutputKind::PerBlobTable,
+            ),
+        ]);
+    }
+
+    if matches!(
+        scenario,
+        Scenario::VoteCensorship | Scenario::Scheduled | Scenario::SmartDataWithholding
+    ) {
+        outputs.push((
+            format!("simulation_results_censorship_{}.txt", suffix),
+            OutputKind::CensorshipTable,
+        ));
+    }
+
+    if matches!(
+        scenario,
+        Scenario::DataWithholding | Scenario::SmartDataWithholding | Scenario::Scheduled
+    ) {
+        outputs.push((
+            format!("simulation_results_das_{}.txt", suffix),
+            OutputKind::DasTable,
+        ));
+    }
+
+    if matches!(
+        scenario,
+        Scenario::SmartDataWithholding | Scenario::Scheduled
+    ) {
+        outputs.push((
+            format!("simulation_results_adversary_{}.txt", suffix),
+            OutputKind::AdversaryTable,
+        ));
+    }
+
+    if scenario != Scenario::Basic {
+        outputs.push((
+            format!("simulation_results_recovery_{}.txt", suffix),
+            OutputKind::RecoveryTable,
+        ));
+    }
+
+    outputs.push((
+        format!("simulation_results_vote_validation_{}.txt", suffix),
+        OutputKind::VoteValidationTable,
+    ));
+
+    if scenario == Scenario::SelfishMining {
+        outputs.push((
+            format!("simulation_results_forks_{}.txt", suffix),
+            OutputKind::ForkTable,
+        ));
+    }
+
+    if scenario == Scenario::Equivocation {
+        outputs.push((
+            format!("simulation_results_slashing_{}.txt", suffix),
+            OutputKind::SlashingTable,
+        ));
+    }
+
+    if scenario == Scenario::Congestion {
+        outputs.push((
+            format!("simulation_results_block_limit_{}.txt", suffix),
+            OutputKind::CongestionTable,
+        ));
+    }
+
+    if scenario == Scenario::Spam {
+        outputs.push((
+            format!("simulation_results_junk_{}.txt", suffix),
+            OutputKind::SpamTable,
+        ));
+    }
+
+    if scenario == Scenario::FeeMarket {
+        outputs.extend([
+            (
+                format!("simulation_results_fees_{}.txt", suffix),
+                OutputKind::FeeTable,
+            ),
+            (
+                format!("simulation_results_fees_{}.csv", suffix),
+                OutputKind::FeeCsv,
+            ),
+        ]);
+    }
+
+    if scenario == Scenario::Scheduled {
+        outputs.push((
+            format!("simulation_results_phases_{}.txt", suffix),
+            OutputKind::PhaseTable,
+        ));
+    }
+
+    if scenario == Scenario::SelectiveWithholding {
+        outputs.push((
+            format!("simulation_results_split_votes_{}.txt", suffix),
+            OutputKind::SelectiveTable,
+        ));
+    }
+
+    outputs
+}
+
+pub fn files(scenario: Scenario) -> Vec<String> {
+    outputs(scenario)
+        .into_iter()
+        .map(|(file, _)| file)
+        .collect()
+}
+
+pub fn write_outputs(result: &SimulationResult) -> io::Result<()> {
+    for (file, kind) in outputs(result.scenario) {
+        let mut writer = BufWriter::new(File::create(file)?);
+        match kind {
+            OutputKind::ResultsCsv => {
+                create_results_csv(&result.blocks, &result.honest_nodes, &mut writer)?
+            }
+            OutputKind::ResultsJson => create_results_json(result, &mut writer)?,
+            OutputKind::Statistics => {
+                let table =
+                    create_statistics_table(&result.blocks, &result.blobs, &result.honest_nodes);
+                write_table_buffered(&table, &mut writer)?;
+                let table = create_throughput_table(&result.blocks, &result.blobs);
+                write_table_buffered(&table, &mut writer)?;
+                let table = create_bandwidth_table(&result.blocks, &result.blobs, &result.settings);
+                write_table_buffered(&table, &mut writer)?;
+                let table = create_encoding_table(&result.blocks, &result.settings);
+                write_table_buffered(&table, &mut writer)?
+            }
+            OutputKind::PerBlockTable => {
+                let table = create_voting_summary_per_block(&result.blocks, &result.honest_nodes);
+                write_table_buffered(&table, &mut writer)?
+            }
+            OutputKind::PerBlobTable => {
+                let table = create_voting_summary_per_blob(&result.blobs);
+                write_table_buffered(&table, &mut writer)?
+            }
+            OutputKind::CensorshipTable => {
+                let metrics = result.censorship.clone().unwrap_or_default();
+                write_table_buffered(&create_censorship_table(&metrics), &mut writer)?
+            }
+            OutputKind::VoteLatencyTable => {
+                let table = create_vote_latency_table(&result.votes, &result.blobs);
+                write_table_buffered(&table, &mut writer)?;
+                let table = create_vote_latency_histogram(&result.votes);
+                write_table_buffered(&table, &mut writer)?
+            }
+            OutputKind::ThroughputCsv => {
+                create_throughput_csv(&result.blocks, &result.blobs, &mut writer)?
+            }
+            OutputKind::DasTable => {
+                let metrics = result.das.clone().unwrap_or_default();
+                write_table_buffered(&create_das_table(&metrics), &mut writer)?;
+                let table = create_detection_sweep_table(metrics.chunks);
+                write_table_buffered(&table, &mut writer)?;
+                let table = create_reconstruction_table(&result.blobs, &result.votes);
+                write_table_buffered(&table, &mut writer)?
+            }
+            OutputKind::SelectiveTable => {
+                let metrics = result.selective.clone().unwrap_or_default();
+                write_table_buffered(&create_selective_table(&metrics), &mut writer)?;
+                let table = create_split_vote_table(&metrics);
+                write_table_buffered(&table, &mut writer)?
+            }
+            OutputKind::AdversaryTable => {
+                let metrics = result.adversary.clone().unwrap_or_default();
+                write_table_buffered(&create_adversary_table(&metrics), &mut writer)?
+            }
+            OutputKind::PhaseTable => {
+                let table = create_phase_table(
+                    &result.phases,
+                    &result.blocks,
+                    &result.blobs,
+                    &result.votes,
+                    &result.honest_nodes,
+                );
+                write_table_buffered(&table, &mut writer)?
+            }
+            OutputKind::RecoveryTable => {
+                let table = create_recovery_table(result.recovery.as_ref());
+                write_table_buffered(&table, &mut writer)?
+            }
+            OutputKind::ForkTable => {
+                let metrics = result.fork.clone().unwrap_or_default();
+                let table = create_fork_table(&metrics, result.blocks.len());
+                write_table_buffered(&table, &mut writer)?;
+                write_table_buffered(&create_safety_table(result), &mut writer)?
+            }
+            OutputKind::VoteValidationTable => {
+                let metrics = result.vote_validation.clone().unwrap_or_default();
+                let table = create_vote_validation_table(&metrics);
+                write_table_buffered(&table, &mut writer)?
+            }
+            OutputKind::SlashingTable => {
+                let metrics = result.equivocation.clone().unwrap_or_default();
+                write_table_buffered(&create_slashing_table(&metrics), &mut writer)?;
+                write_table_buffered(&create_power_table(&metrics), &mut writer)?
+            }
+            OutputKind::CongestionTable => {
+                let metrics = result.congestion.clone().unwrap_or_default();
+                write_table_buffered(&create_congestion_table(&metrics), &mut writer)?;
+                write_table_buffered(&create_pool_delay_table(&metrics), &mut writer)?
+            }
+            OutputKind::SpamTable => write_table_buffered(&create_spam_table(result), &mut writer)?,
+            OutputKind::FeeTable => {
+                let metrics = result.fees.clone().unwrap_or_default();
+                write_table_buffered(&create_fee_table(result), &mut writer)?;
+                write_table_buffered(&create_fee_dynamics_table(&metrics), &mut writer)?;
+                write_table_buffered(&create_fee_censorship_table(result), &mut writer)?
+            }
+            OutputKind::FeeCsv => {
+                let metrics = result.fees.clone().unwrap_or_default();
+                create_fee_csv(&metrics, &mut writer)?
+            }
+            OutputKind::BandwidthCsv => {
+                create_bandwidth_csv(&result.blocks, &result.blobs, &result.settings, &mut writer)?
+            }
+        }
+    }
+    Ok(())
+}
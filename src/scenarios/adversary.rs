@@ -0,0 +1,145 @@
+use crate::models::{AdversaryPolicy, AdversarySettings};
+
+// What the adversary does with one blob in the current block
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    // Malicious committee members vote Yes
+    VoteYes,
+    // Malicious committee members vote No
+    VoteNo,
+    // Malicious committee members cast no vote
+    Abstain,
+    // Withhold the blob's chunks from honest samplers; malicious members vote No
+    Withhold,
+    // Leave honest votes on the blob out of the block when the proposer is malicious;
+    // falls back to `VoteNo` under an honest proposer
+    Censor,
+}
+
+// Public view of one blob still open for voting
+#[derive(Debug, Clone)]
+pub struct BlobView {
+    pub blob_id: usize,
+    pub votes_honest: usize,
+    pub votes_malicious: usize,
+    // Blocks left before the blob confirms, 0 in its final voting block
+    pub blocks_until_confirmation: usize,
+    // Whether the adversary already withholds its chunks
+    pub withheld: bool,
+}
+
+// Everything the adversary can observe when deciding on a block
+#[derive(Debug, Clone)]
+pub struct PublicState<'a> {
+    pub block: usize,
+    pub proposer_malicious: bool,
+    // Honesty of the next block's proposer, when the proposer schedule is predictable
+    pub next_proposer_malicious: Option<bool>,
+    pub blobs: &'a [BlobView],
+}
+
+// An adversary that adapts to the public state each block
+pub trait Adversary {
+    fn name(&self) -> String {
+        "custom".to_string()
+    }
+
+    fn decide(&mut self, state: &PublicState, blob: &BlobView) -> Action;
+}
+
+// Votes No on everything, like the malicious nodes of the basic scenario
+pub struct Passive;
+
+impl Adversary for Passive {
+    fn name(&self) -> String {
+        "passive".to_string()
+    }
+
+    fn decide(&mut self, _state: &PublicState, _blob: &BlobView) -> Action {
+        Action::VoteNo
+    }
+}
+
+// Backs a blob until it has `threshold` honest votes, then withholds its data
+pub struct ThresholdWithholding {
+    pub threshold: usize,
+}
+
+impl Adversary for ThresholdWithholding {
+    fn name(&self) -> String {
+        format!("threshold (threshold = {})", self.threshold)
+    }
+
+    fn decide(&mut self, _state: &PublicState, blob: &BlobView) -> Action {
+        if blob.withheld || blob.votes_honest >= self.threshold {
+            Action::Withhold
+        } else {
+            Action::VoteYes
+        }
+    }
+}
+
+// Backs a blob and withholds its data only in the last `horizon` blocks before confirmation
+pub struct LastMinuteWithholding {
+    pub horizon: usize,
+}
+
+impl Adversary for LastMinuteWithholding {
+    fn name(&self) -> String {
+        format!("last_minute (horizon = {})", self.horizon)
+    }
+
+    fn decide(&mut self, _state: &PublicState, blob: &BlobView) -> Action {
+        if blob.withheld || blob.blocks_until_confirmation <= self.horizon {
+            Action::Withhold
+        } else {
+            Action::VoteYes
+        }
+    }
+}
+
+// Censors honest votes on blobs close to confirmation whenever it proposes. Otherwise it
+// votes No on blobs with `threshold` honest votes, and abstains while one of its own
+// proposers is up next so that block can censor instead.
+pub struct ProposerAware {
+    pub threshold: usize,
+    pub horizon: usize,
+}
+
+impl Adversary for ProposerAware {
+    fn name(&self) -> String {
+        format!(
+            "proposer_aware (threshold = {}, horizon = {})",
+            self.threshold, self.horizon
+        )
+    }
+
+    fn decide(&mut self, state: &PublicState, blob: &BlobView) -> Action {
+        if state.proposer_malicious && blob.blocks_until_confirmation <= self.horizon {
+            Action::Censor
+        } else if state.next_proposer_malicious == Some(true) {
+            Action::Abstain
+        } else if blob.votes_honest >= self.threshold {
+            Action::VoteNo
+        } else {
+            Action::VoteYes
+        }
+    }
+}
+
+// Built-in adversary configured by `[adversary]`
+pub(crate) fn from_settings(settings: &AdversarySettings) -> Box<dyn Adversary> {
+    match settings.policy {
+        AdversaryPolicy::Passive => Box::new(Passive),
+        AdversaryPolicy::Threshold => Box::new(ThresholdWithholding {
+            threshold: settings.threshold,
+        }),
+        AdversaryPolicy::LastMinute => Box::new(LastMinuteWithholding {
+            horizon: settings.horizon,
+        }),
+        AdversaryPolicy::ProposerAware => Box::new(ProposerAware {
+            threshold: settings.threshold,
+            horizon: settings.horizon,
+        }),
+    }
+}
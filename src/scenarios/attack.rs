@@ -0,0 +1,335 @@
+use super::adversary::{Action, Adversary, BlobView, PublicState};
+use super::blob_queue::BlobQueue;
+use super::das::Sampler;
+use super::pending::PendingVotes;
+use super::vote_gate::VoteGate;
+use crate::models::{
+    AdversaryMetrics, Blob, Block, CensorshipMetrics, PhaseRecord, Settings, SimulationResult,
+    VoteMessage, VoteRecord,
+};
+use crate::simulation::Scenario;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use std::collections::{HashMap, HashSet};
+
+// What the adversary does to one open blob in the current block
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Attack {
+    // Chunks are withheld from honest samplers
+    pub(crate) withhold: bool,
+    // Honest votes are left out of the block and wait in the pending pool
+    pub(crate) censor: bool,
+    // How malicious committee members vote; None abstains
+    pub(crate) malicious_vote: Option<bool>,
+}
+
+impl Attack {
+    // Malicious members vote No and nothing else happens
+    pub(crate) const NONE: Attack = Attack {
+        withhold: false,
+        censor: false,
+        malicious_vote: Some(false),
+    };
+
+    // Adds an adaptive adversary's action on top of this attack; the action decides
+    // the malicious vote
+    fn with_action(self, action: Action) -> Self {
+        Self {
+            withhold: self.withhold || action == Action::Withhold,
+            censor: self.censor || action == Action::Censor,
+            malicious_vote: match action {
+                Action::VoteYes => Some(true),
+                Action::Abstain => None,
+                Action::VoteNo | Action::Withhold | Action::Censor => Some(false),
+            },
+        }
+    }
+}
+
+// The proposer of the block being built and how many blobs are open for its votes
+pub(crate) struct BlockStart {
+    pub(crate) proposer_honest: bool,
+    pub(crate) open_blobs: usize,
+}
+
+// Run state shared by the scenarios whose adversary picks an attack per blob and
+// block: adaptive policies, scheduled phases, or both. Each block is built in two
+// steps, `begin_block` and `finish_block`, so a scenario can look at the block's
+// proposer and open blobs before choosing what to attack.
+pub(crate) struct AttackRun<'a> {
+    config: &'a Settings,
+    adversary: &'a mut dyn Adversary,
+    honest_nodes: HashSet<usize>,
+    all_nodes: Vec<usize>,
+    blobs: HashMap<usize, Blob>,
+    blob_queue: BlobQueue,
+    gate: VoteGate,
+    blocks: Vec<Block>,
+    vote_records: Vec<VoteRecord>,
+    sampler: Sampler,
+    censorship: CensorshipMetrics,
+    pending_votes: PendingVotes,
+    metrics: AdversaryMetrics,
+    // Proposers are drawn one block ahead so a predictable schedule can be exposed
+    next_proposer: usize,
+    block_proposer: usize,
+    included_blobs: Vec<usize>,
+}
+
+impl<'a> AttackRun<'a> {
+    pub(crate) fn new(
+        config: &'a Settings,
+        adversary: &'a mut dyn Adversary,
+        rng: &mut StdRng,
+    ) -> Self {
+        let all_nodes: Vec<usize> = (0..config.total_nodes).collect();
+        let next_proposer = *all_nodes.choose(rng).unwrap();
+        Self {
+            config,
+            metrics: AdversaryMetrics {
+                policy: adversary.name(),
+                ..Default::default()
+            },
+            adversary,
+            honest_nodes: (0..(config.total_nodes - config.malicious_nodes)).collect(),
+            all_nodes,
+            blobs: HashMap::new(),
+            blob_queue: BlobQueue::new(config),
+            gate: VoteGate::new(config),
+            blocks: Vec::new(),
+            vote_records: Vec::new(),
+            sampler: Sampler::new(&config.das),
+            censorship: CensorshipMetrics::default(),
+            pending_votes: PendingVotes::new(),
+            next_proposer,
+            block_proposer: next_proposer,
+            included_blobs: Vec::new(),
+        }
+    }
+
+    // New blobs arrive and the proposer of `block_number` takes blobs from the mempool
+    pub(crate) fn begin_block(&mut self, block_number: usize, rng: &mut StdRng) -> BlockStart {
+        self.block_proposer = self.next_proposer;
+        self.blob_queue.arrive(block_number, rng, &mut self.blobs);
+        self.included_blobs = self.blob_queue.include(block_number, &mut self.blobs);
+        BlockStart {
+            proposer_honest: self.honest_nodes.contains(&self.block_proposer),
+            open_blobs: self.blob_queue.unconfirmed().len(),
+        }
+    }
+
+    // A committee votes on every open blob under `attack`, with the adaptive adversary
+    // also consulted per blob when `adaptive` is set, and the block is produced
+    pub(crate) fn finish_block(
+        &mut self,
+        block_number: usize,
+        rng: &mut StdRng,
+        attack: Attack,
+        adaptive: bool,
+    ) -> &Block {
+        let proposer_honest = self.honest_nodes.contains(&self.block_proposer);
+
+        // Randomly select the nodes that will vote in this block
+        let selected_nodes: Vec<usize> = self
+            .all_nodes
+            .choose_multiple(rng, self.config.nodes_per_block)
+            .cloned()
+            .collect();
+        self.gate.open_round(block_number, &selected_nodes);
+        self.next_proposer = *self.all_nodes.choose(rng).unwrap();
+
+        // The adversary sees the current tallies and decides on every open blob at once
+        let views = self.views(block_number);
+        let state = PublicState {
+            block: block_number,
+            proposer_malicious: !proposer_honest,
+            next_proposer_malicious: self
+                .config
+                .adversary
+                .predictable_proposer
+                .then(|| !self.honest_nodes.contains(&self.next_proposer)),
+            blobs: &views,
+        };
+
+        let mut block_votes: HashMap<usize, Vec<Option<bool>>> = HashMap::new();
+        for view in &views {
+            let attack = if adaptive {
+                attack.with_action(self.decide(&state, view, proposer_honest))
+            } else {
+                attack
+            };
+            let votes = self.vote(
+                view.blob_id,
+                block_number,
+                &selected_nodes,
+                attack,
+                proposer_honest,
+                rng,
+            );
+            block_votes.insert(view.blob_id, votes);
+        }
+
+        let buffered_votes = if proposer_honest {
+            self.pending_votes.drain_into(
+                block_number,
+                &mut self.gate,
+                &mut self.blobs,
+                &mut self.vote_records,
+                &mut self.censorship,
+            )
+        } else {
+            HashMap::new()
+        };
+
+        let block = Block {
+            number: block_number,
+            proposer: self.block_proposer,
+            blobs: std::mem::take(&mut self.included_blobs),
+            selected_nodes,
+            votes: block_votes,
+            buffered_votes,
+            header: None,
+        };
+
+        self.gate.attack(&block, &self.blobs, rng);
+        // Confirm blobs when they are k blocks deep
+        let confirmed = self.blob_queue.confirm(block.number, &mut self.blobs);
+        self.censorship.lost_votes += self.pending_votes.drop_confirmed(&confirmed);
+
+        self.blocks.push(block);
+        self.blocks.last().unwrap()
+    }
+
+    pub(crate) fn into_result(
+        mut self,
+        scenario: Scenario,
+        phases: Vec<PhaseRecord>,
+    ) -> SimulationResult {
+        let das_metrics = self.sampler.finish(&mut self.blobs);
+        self.censorship.pending_votes = self.pending_votes.len();
+        self.metrics.censored_votes = self.censorship.censored_votes;
+
+        SimulationResult {
+            scenario,
+            settings: self.config.clone(),
+            honest_nodes: self.honest_nodes,
+            blocks: self.blocks,
+            blobs: self.blobs,
+            votes: self.vote_records,
+            censorship: Some(self.censorship),
+            das: Some(das_metrics),
+            selective: None,
+            adversary: Some(self.metrics),
+            fork: None,
+            equivocation: None,
+            congestion: None,
+            spam: None,
+            fees: None,
+            phases,
+            recovery: None,
+            vote_validation: Some(self.gate.finish()),
+        }
+    }
+
+    fn views(&self, block_number: usize) -> Vec<BlobView> {
+        self.blob_queue
+            .unconfirmed()
+            .iter()
+            .map(|blob_id| {
+                let blob = &self.blobs[blob_id];
+                BlobView {
+                    blob_id: *blob_id,
+                    votes_honest: blob.votes_honest,
+                    votes_malicious: blob.votes_malicious,
+                    blocks_until_confirmation: (blob.included_at.unwrap()
+                        + self.config.confirmation_depth)
+                        .saturating_sub(block_number + 1),
+                    withheld: !blob.withheld_chunks.is_empty(),
+                }
+            })
+            .collect()
+    }
+
+    // The adversary's action on one blob, counted; an honest proposer cannot censor
+    fn decide(&mut self, state: &PublicState, view: &BlobView, proposer_honest: bool) -> Action {
+        let action = match self.adversary.decide(state, view) {
+            Action::Censor if proposer_honest => Action::VoteNo,
+            action => action,
+        };
+        match action {
+            Action::VoteYes => self.metrics.vote_yes += 1,
+            Action::VoteNo => self.metrics.vote_no += 1,
+            Action::Abstain => self.metrics.abstain += 1,
+            Action::Withhold => self.metrics.withhold += 1,
+            Action::Censor => self.metrics.censor += 1,
+        }
+        action
+    }
+
+    // Votes of the committee on one blob, in committee order
+    fn vote(
+        &mut self,
+        blob_id: usize,
+        block_number: usize,
+        selected_nodes: &[usize],
+        attack: Attack,
+        proposer_honest: bool,
+        rng: &mut StdRng,
+    ) -> Vec<Option<bool>> {
+        // Chunks withheld earlier are served again once the blob is no longer attacked
+        let blob = self.blobs.get_mut(&blob_id).unwrap();
+        self.sampler.serve(blob, attack.withhold, rng);
+
+        let mut votes = Vec::new();
+        for &node in selected_nodes {
+            let honest = self.honest_nodes.contains(&node);
+            let decision = if honest {
+                // Honest nodes sample chunks and vote Yes only if every sample is served
+                let available = self.sampler.vote(blob, rng);
+                if attack.censor {
+                    self.pending_votes.push(
+                        VoteRecord::censored(
+                            node,
+                            blob_id,
+                            available,
+                            true,
+                            block_number,
+                            proposer_honest,
+                        ),
+                        &mut self.vote_records,
+                    );
+                    self.censorship.censored_votes += 1;
+                    votes.push(None);
+                    continue;
+                }
+                available
+            } else {
+                let Some(decision) = attack.malicious_vote else {
+                    votes.push(None);
+                    continue;
+                };
+                decision
+            };
+            let message = VoteMessage::sign(node, blob_id, block_number, decision);
+            if self.gate.admit(&message, blob, block_number).is_err() {
+                votes.push(None);
+                continue;
+            }
+            if honest && decision {
+                blob.votes_honest += 1;
+            } else if !honest && !decision {
+                blob.votes_malicious += 1;
+            }
+            self.vote_records.push(VoteRecord::included(
+                node,
+                blob_id,
+                decision,
+                honest,
+                block_number,
+                proposer_honest,
+            ));
+            votes.push(Some(decision));
+        }
+        votes
+    }
+}
@@ -1,92 +1,112 @@
+use super::blob_queue::BlobQueue;
+use super::vote_gate::VoteGate;
+use crate::models::{Blob, Block, Settings, SimulationResult, VoteMessage, VoteRecord};
+use crate::simulation::Scenario;
+use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
-use sim::{
-    models::{Blob, Block, Settings},
-    utils::csv_writer::create_results_csv,
-};
 use std::collections::{HashMap, HashSet};
-use std::fs::File;
-use std::io::BufWriter;
-
-#[allow(dead_code)]
-pub fn run() {
-    let config = Settings::load("config");
-    let mut rng = rand::thread_rng();
+use tracing::debug;
 
+pub(crate) fn run(
+    config: &Settings,
+    rng: &mut StdRng,
+    on_block: &mut dyn FnMut(&Block),
+) -> SimulationResult {
     let honest_nodes: HashSet<usize> = (0..(config.total_nodes - config.malicious_nodes)).collect();
-    let _malicious_nodes: HashSet<usize> =
-        ((config.total_nodes - config.malicious_nodes)..config.total_nodes).collect();
-    let all_nodes: HashSet<usize> = (0..config.total_nodes).collect();
+    let all_nodes: Vec<usize> = (0..config.total_nodes).collect();
 
     let mut blobs: HashMap<usize, Blob> = HashMap::new();
-    let mut unconfirmed_blobs: HashSet<usize> = HashSet::new();
-    let mut next_blob_id = 0;
+    let mut blob_queue = BlobQueue::new(config);
+    let mut gate = VoteGate::new(config);
 
     let mut blocks: Vec<Block> = Vec::new();
+    let mut vote_records: Vec<VoteRecord> = Vec::new();
 
     for block in 1..=config.total_blocks {
-        let block_proposer = *all_nodes
-            .iter()
-            .collect::<Vec<_>>()
-            .choose(&mut rng)
-            .unwrap();
+        let block_proposer = all_nodes.choose(rng).unwrap();
+        let proposer_honest = honest_nodes.contains(block_proposer);
 
-        // Create new blob for this block
-        let new_blob = sim::models::Blob::new(next_blob_id);
-        blobs.insert(next_blob_id, new_blob);
-        unconfirmed_blobs.insert(next_blob_id);
-        next_blob_id += 1;
+        blob_queue.arrive(block, rng, &mut blobs);
+        let included_blobs = blob_queue.include(block, &mut blobs);
 
         // Select nodes for this block
         let selected_nodes: Vec<usize> = all_nodes
-            .iter()
-            .cloned()
-            .collect::<Vec<_>>()
-            .choose_multiple(&mut rng, config.nodes_per_block)
+            .choose_multiple(rng, config.nodes_per_block)
             .cloned()
             .collect();
+        gate.open_round(block, &selected_nodes);
 
         // Create votes for each unconfirmed blob
         let mut block_votes: HashMap<usize, Vec<Option<bool>>> = HashMap::new();
 
-        for &blob_id in &unconfirmed_blobs {
+        for &blob_id in blob_queue.unconfirmed() {
+            let blob = blobs.get_mut(&blob_id).unwrap();
             let mut votes = Vec::with_capacity(selected_nodes.len());
 
             for &node in &selected_nodes {
-                let vote = if honest_nodes.contains(&node) {
-                    blobs.get_mut(&blob_id).unwrap().votes_honest += 1;
-                    Some(true)
+                let honest = honest_nodes.contains(&node);
+                let message = VoteMessage::sign(node, blob_id, block, honest);
+                if gate.admit(&message, blob, block).is_err() {
+                    votes.push(None);
+                    continue;
+                }
+                if honest {
+                    blob.votes_honest += 1;
                 } else {
-                    blobs.get_mut(&blob_id).unwrap().votes_malicious += 1;
-                    Some(false)
-                };
-                votes.push(vote);
+                    blob.votes_malicious += 1;
+                }
+                vote_records.push(VoteRecord::included(
+                    node,
+                    blob_id,
+                    honest,
+                    honest,
+                    block,
+                    proposer_honest,
+                ));
+                votes.push(Some(honest));
             }
-
             block_votes.insert(blob_id, votes);
         }
 
-        let block = sim::models::Block {
+        let block = Block {
             number: block,
             proposer: *block_proposer,
+            blobs: included_blobs,
             selected_nodes,
             votes: block_votes,
             buffered_votes: HashMap::new(),
+            header: None,
         };
 
-        if block.number >= config.confirmation_depth {
-            let confirmed_blob_id = block.number - config.confirmation_depth;
-            if let Some(blob) = blobs.get_mut(&confirmed_blob_id) {
-                blob.is_confirmed = true;
-                unconfirmed_blobs.remove(&confirmed_blob_id);
-            }
-        }
-        println!("Block Number: {}", block.number);
+        gate.attack(&block, &blobs, rng);
+        blob_queue.confirm(block.number, &mut blobs);
+        debug!(
+            block = block.number,
+            proposer = block.proposer,
+            "block produced"
+        );
+        on_block(&block);
         blocks.push(block);
     }
 
-    let file = File::create("simulation_results.csv").expect("Unable to create file");
-    let mut writer = BufWriter::new(file);
-    create_results_csv(&blocks, &honest_nodes, &mut writer).expect("Unable to write CSV");
-
-    println!("Simulation complete. Results written to 'simulation_results_basic.txt'.");
+    SimulationResult {
+        scenario: Scenario::Basic,
+        settings: config.clone(),
+        honest_nodes,
+        blocks,
+        blobs,
+        votes: vote_records,
+        censorship: None,
+        das: None,
+        selective: None,
+        adversary: None,
+        fork: None,
+        equivocation: None,
+        congestion: None,
+        spam: None,
+        fees: None,
+        phases: Vec::new(),
+        recovery: None,
+        vote_validation: Some(gate.finish()),
+    }
 }
@@ -0,0 +1,282 @@
+use crate::models::{Blob, BlobArrival, BlobSize, Settings};
+use rand::rngs::StdRng;
+use rand::Rng;
+use rand_distr::{Distribution, LogNormal, Poisson};
+use std::collections::{BTreeSet, HashMap, VecDeque};
+
+// Blob lifecycle shared by the scenarios: arrival in the mempool, inclusion
+// by a proposer, voting while unconfirmed, then confirmation.
+pub(crate) struct BlobQueue {
+    arrival: BlobArrival,
+    blob_size: BlobSize,
+    capacity: usize,
+    // Bytes `block_limit.max_bytes` leaves for blob payloads after the header
+    payload_bytes: usize,
+    confirmation_depth: usize,
+    // Fractional arrivals carried over between blocks for `BlobArrival::Fixed`
+    arrival_credit: f64,
+    next_blob_id: usize,
+    mempool: VecDeque<usize>,
+    // Ordered so votes are generated in the same order for a given seed
+    unconfirmed: BTreeSet<usize>,
+}
+
+impl BlobQueue {
+    pub(crate) fn new(config: &Settings) -> Self {
+        Self {
+            arrival: config.blob_arrival.clone(),
+            blob_size: config.bandwidth.blob_size.clone(),
+            capacity: config.max_blobs_per_block.unwrap_or(usize::MAX),
+            payload_bytes: config
+                .block_limit
+                .max_bytes
+                .map_or(usize::MAX, |max_bytes| {
+                    max_bytes.saturating_sub(config.bandwidth.header_bytes)
+                }),
+            confirmation_depth: config.confirmation_depth,
+            arrival_credit: 0.0,
+            next_blob_id: 0,
+            mempool: VecDeque::new(),
+            unconfirmed: BTreeSet::new(),
+        }
+    }
+
+    // Replaces the default of `max_blobs_per_block`, or no limit, for scenarios with
+    // their own blob limit
+    pub(crate) fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    // Adds the blobs arriving in `block` to the mempool
+    pub(crate) fn arrive(
+        &mut self,
+        block: usize,
+        rng: &mut StdRng,
+        blobs: &mut HashMap<usize, Blob>,
+    ) -> Vec<usize> {
+        let arrivals = match &self.arrival {
+            BlobArrival::Fixed { rate } => {
+                let before = self.arrival_credit.floor();
+                self.arrival_credit += rate.max(0.0);
+                (self.arrival_credit.floor() - before) as usize
+            }
+            BlobArrival::Poisson { rate } if *rate > 0.0 => {
+                Poisson::new(*rate).unwrap().sample(rng) as usize
+            }
+            BlobArrival::Poisson { .. } => 0,
+            BlobArrival::Trace { counts } if !counts.is_empty() => {
+                counts[(block - 1) % counts.len()]
+            }
+            BlobArrival::Trace { .. } => 0,
+        };
+
+        (0..arrivals)
+            .map(|_| {
+                let size = self.sample_size(rng);
+                self.submit(block, size, blobs)
+            })
+            .collect()
+    }
+
+    // Adds one blob of `size` bytes submitted in `block` to the back of the mempool
+    pub(crate) fn submit(
+        &mut self,
+        block: usize,
+        size: usize,
+        blobs: &mut HashMap<usize, Blob>,
+    ) -> usize {
+        let blob_id = self.next_blob_id;
+        self.next_blob_id += 1;
+        blobs.insert(blob_id, Blob::new(blob_id, block, size));
+        self.mempool.push_back(blob_id);
+        blob_id
+    }
+
+    // Fixed sizes draw nothing from the RNG, so they leave the rest of the run unchanged
+    fn sample_size(&self, rng: &mut StdRng) -> usize {
+        match self.blob_size {
+            BlobSize::Fixed { bytes } => bytes,
+            BlobSize::Uniform { min, max } => rng.gen_range(min..=max),
+            BlobSize::LogNormal { mean, std_dev } => {
+                // Parameters of the underlying normal that give this mean and deviation
+                let sigma2 = (1.0 + (std_dev / mean).powi(2)).ln();
+                let mu = mean.ln() - sigma2 / 2.0;
+                LogNormal::new(mu, sigma2.sqrt())
+                    .unwrap()
+                    .sample(rng)
+                    .round() as usize
+            }
+        }
+    }
+
+    // The candidates, in order, that fit in one block: at most `capacity` blobs whose
+    // payloads fit in `payload_bytes`. A blob too large for the bytes left is skipped
+    // and waits for a later block.
+    pub(crate) fn fit(
+        &self,
+        candidates: impl IntoIterator<Item = usize>,
+        blobs: &HashMap<usize, Blob>,
+    ) -> Vec<usize> {
+        let mut bytes_left = self.payload_bytes;
+        let mut fitting = Vec::new();
+        for blob_id in candidates {
+            if fitting.len() == self.capacity {
+                break;
+            }
+            let size = blobs[&blob_id].size;
+            if size <= bytes_left {
+                bytes_left -= size;
+                fitting.push(blob_id);
+            }
+        }
+        fitting
+    }
+
+    // The proposer of `block` takes the oldest blobs from the mempool, up to capacity
+    pub(crate) fn include(&mut self, block: usize, blobs: &mut HashMap<usize, Blob>) -> Vec<usize> {
+        let included = self.fit(self.pending(), blobs);
+        self.take(block, &included, blobs);
+        included
+    }
+
+    // Blobs waiting in the mempool, oldest first
+    pub(crate) fn pending(&self) -> impl Iterator<Item = usize> + '_ {
+        self.mempool.iter().copied()
+    }
+
+    // The proposer of `block` takes the chosen mempool blobs in the given order, as
+    // many as fit in capacity
+    pub(crate) fn include_chosen(
+        &mut self,
+        block: usize,
+        chosen: &[usize],
+        blobs: &mut HashMap<usize, Blob>,
+    ) -> Vec<usize> {
+        assert!(
+            chosen.iter().all(|blob_id| self.mempool.contains(blob_id)),
+            "only blobs waiting in the mempool can be included"
+        );
+        let included = self.fit(chosen.iter().copied(), blobs);
+        self.take(block, &included, blobs);
+        included
+    }
+
+    fn take(&mut self, block: usize, included: &[usize], blobs: &mut HashMap<usize, Blob>) {
+        self.mempool.retain(|blob_id| !included.contains(blob_id));
+        for &blob_id in included {
+            blobs.get_mut(&blob_id).unwrap().included_at = Some(block);
+            self.unconfirmed.insert(blob_id);
+        }
+    }
+
+    // Blobs currently open for votes
+    pub(crate) fn unconfirmed(&self) -> &BTreeSet<usize> {
+        &self.unconfirmed
+    }
+
+    // Confirms every blob that has been voted on in `confirmation_depth` blocks
+    pub(crate) fn confirm(&mut self, block: usize, blobs: &mut HashMap<usize, Blob>) -> Vec<usize> {
+        let confirmed: Vec<usize> = self
+            .unconfirmed
+            .iter()
+            .copied()
+            .filter(|blob_id| {
+                let included_at = blobs[blob_id].included_at.unwrap();
+                block + 1 >= included_at + self.confirmation_depth
+            })
+            .collect();
+
+        for blob_id in &confirmed {
+            let blob = blobs.get_mut(blob_id).unwrap();
+            blob.is_confirmed = true;
+            blob.confirmed_at = Some(block);
+            self.unconfirmed.remove(blob_id);
+        }
+        confirmed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    // A queue with the given blob limit and block size, holding blobs of `sizes`
+    // submitted in block 1
+    fn queue(
+        capacity: Option<usize>,
+        max_bytes: Option<usize>,
+        sizes: &[usize],
+    ) -> (BlobQueue, HashMap<usize, Blob>) {
+        let mut config = Settings::for_tests();
+        config.max_blobs_per_block = capacity;
+        config.block_limit.max_bytes = max_bytes;
+        let mut queue = BlobQueue::new(&config);
+        let mut blobs = HashMap::new();
+        for &size in sizes {
+            queue.submit(1, size, &mut blobs);
+        }
+        (queue, blobs)
+    }
+
+    #[test]
+    fn include_takes_oldest_blobs_up_to_capacity() {
+        let (mut queue, mut blobs) = queue(Some(2), None, &[100, 100, 100]);
+
+        assert_eq!(queue.include(2, &mut blobs), vec![0, 1]);
+        assert_eq!(queue.pending().collect::<Vec<_>>(), vec![2]);
+        assert_eq!(blobs[&0].included_at, Some(2));
+        assert_eq!(blobs[&2].included_at, None);
+        assert_eq!(
+            queue.unconfirmed().iter().copied().collect::<Vec<_>>(),
+            vec![0, 1]
+        );
+    }
+
+    #[test]
+    fn include_skips_blobs_over_the_byte_budget() {
+        // 1000 bytes minus the 512-byte header leaves 488 for payloads
+        let (mut queue, mut blobs) = queue(None, Some(1000), &[300, 300, 100]);
+
+        assert_eq!(queue.include(2, &mut blobs), vec![0, 2]);
+        assert_eq!(queue.pending().collect::<Vec<_>>(), vec![1]);
+    }
+
+    #[test]
+    fn include_chosen_keeps_the_given_order() {
+        let (mut queue, mut blobs) = queue(Some(2), None, &[100, 100, 100]);
+
+        assert_eq!(queue.include_chosen(2, &[2, 0, 1], &mut blobs), vec![2, 0]);
+        assert_eq!(queue.pending().collect::<Vec<_>>(), vec![1]);
+    }
+
+    #[test]
+    fn confirm_waits_for_confirmation_depth() {
+        // Blobs included in block 2 are voted on in blocks 2, 3 and 4
+        let (mut queue, mut blobs) = queue(None, None, &[100]);
+        queue.include(2, &mut blobs);
+
+        assert!(queue.confirm(3, &mut blobs).is_empty());
+        assert!(!blobs[&0].is_confirmed);
+        assert_eq!(queue.confirm(4, &mut blobs), vec![0]);
+        assert!(blobs[&0].is_confirmed);
+        assert_eq!(blobs[&0].confirmed_at, Some(4));
+        assert_eq!(blobs[&0].confirmation_latency(), Some(3));
+        assert!(queue.unconfirmed().is_empty());
+    }
+
+    #[test]
+    fn fixed_fractional_rate_spreads_arrivals() {
+        let mut config = Settings::for_tests();
+        config.blob_arrival = BlobArrival::Fixed { rate: 0.5 };
+        let mut queue = BlobQueue::new(&config);
+        let mut blobs = HashMap::new();
+        let mut rng = StdRng::seed_from_u64(1);
+
+        let arrivals: Vec<usize> = (1..=4)
+            .map(|block| queue.arrive(block, &mut rng, &mut blobs).len())
+            .collect();
+        assert_eq!(arrivals, vec![0, 1, 0, 1]);
+    }
+}
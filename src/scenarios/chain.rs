@@ -0,0 +1,248 @@
+use crate::models::{Block, BlockHeader, ForkChoice, ZERO_HASH};
+use std::collections::HashMap;
+
+// A block in the tree of every branch produced, public or private
+pub(crate) struct TreeBlock {
+    pub(crate) block: Block,
+    // Index of the parent in the tree, None for the first block
+    pub(crate) parent: Option<usize>,
+    pub(crate) height: usize,
+    pub(crate) malicious: bool,
+    pub(crate) published: bool,
+    // Blobs included on the path up to and including this block; blob ids arrive in order
+    pub(crate) blobs_through: usize,
+    // Votes included on the path up to and including this block
+    pub(crate) chain_votes: usize,
+}
+
+// All blocks with their parent links; the head is picked among published blocks by
+// the fork-choice rule, ties going to the block seen first
+pub(crate) struct BlockTree {
+    blocks: Vec<TreeBlock>,
+    fork_choice: ForkChoice,
+    head: Option<usize>,
+}
+
+impl BlockTree {
+    pub(crate) fn new(fork_choice: ForkChoice) -> Self {
+        Self {
+            blocks: Vec::new(),
+            fork_choice,
+            head: None,
+        }
+    }
+
+    pub(crate) fn get(&self, index: usize) -> &TreeBlock {
+        &self.blocks[index]
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.blocks.len()
+    }
+
+    pub(crate) fn head(&self) -> Option<usize> {
+        self.head
+    }
+
+    // Adds an unpublished block on top of `parent`, linked by header hash, and returns
+    // its index
+    pub(crate) fn push(
+        &mut self,
+        parent: Option<usize>,
+        mut block: Block,
+        blobs_through: usize,
+        malicious: bool,
+    ) -> usize {
+        let (height, parent_votes) = match parent {
+            Some(parent) => (
+                self.blocks[parent].height + 1,
+                self.blocks[parent].chain_votes,
+            ),
+            None => (1, 0),
+        };
+        let parent_hash = parent
+            .and_then(|parent| self.blocks[parent].block.header.as_ref())
+            .map_or(ZERO_HASH, |header| header.hash.as_str());
+        block.header = Some(BlockHeader::new(parent_hash, &block));
+        let votes = block.votes.values().flatten().flatten().count()
+            + block.buffered_votes.values().map(Vec::len).sum::<usize>();
+        self.blocks.push(TreeBlock {
+            block,
+            parent,
+            height,
+            malicious,
+            published: false,
+            blobs_through,
+            chain_votes: parent_votes + votes,
+        });
+        self.blocks.len() - 1
+    }
+
+    // Publishes a block with its unpublished ancestors and reapplies the fork-choice rule
+    pub(crate) fn publish(&mut self, index: usize) {
+        let unpublished: Vec<usize> = self
+            .ancestors(Some(index))
+            .take_while(|&ancestor| !self.blocks[ancestor].published)
+            .collect();
+        for &block in unpublished.iter().rev() {
+            self.blocks[block].published = true;
+            if self.head.is_none_or(|head| self.better(block, head)) {
+                self.head = Some(block);
+            }
+        }
+    }
+
+    fn better(&self, a: usize, b: usize) -> bool {
+        let (a, b) = (&self.blocks[a], &self.blocks[b]);
+        match self.fork_choice {
+            ForkChoice::Longest => a.height > b.height,
+            ForkChoice::MostVotes => (a.chain_votes, a.height) > (b.chain_votes, b.height),
+        }
+    }
+
+    // `index` and its ancestors, newest first
+    pub(crate) fn ancestors(&self, index: Option<usize>) -> impl Iterator<Item = usize> + '_ {
+        std::iter::successors(index, |&block| self.blocks[block].parent)
+    }
+
+    // Deepest block both `a` and `b` descend from
+    pub(crate) fn common_ancestor(&self, a: usize, b: usize) -> Option<usize> {
+        let (mut a, mut b) = (Some(a), Some(b));
+        while let (Some(x), Some(y)) = (a, b) {
+            if x == y {
+                return Some(x);
+            }
+            if self.blocks[x].height >= self.blocks[y].height {
+                a = self.blocks[x].parent;
+            } else {
+                b = self.blocks[y].parent;
+            }
+        }
+        None
+    }
+
+    // Blocks from the first one up to `head`
+    pub(crate) fn path(&self, head: Option<usize>) -> Vec<usize> {
+        let mut path: Vec<usize> = self.ancestors(head).collect();
+        path.reverse();
+        path
+    }
+
+    // Blobs that `index` confirms, with whether each was accepted: those included
+    // `depth - 1` blocks below it, by Yes and No votes over the blocks in between
+    pub(crate) fn confirmations(&self, index: usize, depth: usize) -> Vec<(usize, bool)> {
+        let window: Vec<usize> = self.ancestors(Some(index)).take(depth.max(1)).collect();
+        if window.len() < depth.max(1) {
+            return Vec::new();
+        }
+        let base = &self.blocks[*window.last().unwrap()].block;
+        let mut tallies: HashMap<usize, (usize, usize)> = HashMap::new();
+        for &block in &window {
+            let block = &self.blocks[block].block;
+            for blob_id in &base.blobs {
+                let direct = block.votes.get(blob_id).into_iter().flatten().flatten();
+                let buffered = block
+                    .buffered_votes
+                    .get(blob_id)
+                    .into_iter()
+                    .flatten()
+                    .map(|vote| &vote.decision);
+                let (yes, no) = tallies.entry(*blob_id).or_default();
+                for &decision in direct.chain(buffered) {
+                    *yes += usize::from(decision);
+                    *no += usize::from(!decision);
+                }
+            }
+        }
+        base.blobs
+            .iter()
+            .map(|blob_id| {
+                let (yes, no) = tallies[blob_id];
+                (*blob_id, yes > no)
+            })
+            .collect()
+    }
+
+    // Confirmed blobs and their verdicts as seen from `head`
+    pub(crate) fn confirmed_view(&self, head: Option<usize>, depth: usize) -> HashMap<usize, bool> {
+        self.path(head)
+            .into_iter()
+            .flat_map(|block| self.confirmations(block, depth))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A block with one blob and `votes` Yes votes on it
+    fn block(number: usize, votes: usize) -> Block {
+        Block {
+            number,
+            proposer: 0,
+            blobs: vec![number],
+            selected_nodes: (0..votes).collect(),
+            votes: HashMap::from([(number, vec![Some(true); votes])]),
+            buffered_votes: HashMap::new(),
+            header: None,
+        }
+    }
+
+    // Builds a public block `a` and a two-block branch `b`, `c` on the same parent,
+    // with the given vote counts, and publishes them in that order
+    fn fork(fork_choice: ForkChoice, votes: [usize; 3]) -> (BlockTree, [usize; 3]) {
+        let mut tree = BlockTree::new(fork_choice);
+        let root = tree.push(None, block(1, 0), 1, false);
+        tree.publish(root);
+        let a = tree.push(Some(root), block(2, votes[0]), 2, false);
+        tree.publish(a);
+        let b = tree.push(Some(root), block(3, votes[1]), 2, true);
+        let c = tree.push(Some(b), block(4, votes[2]), 3, true);
+        (tree, [a, b, c])
+    }
+
+    #[test]
+    fn longest_chain_prefers_height_and_the_first_seen_tie() {
+        let (mut tree, [a, b, c]) = fork(ForkChoice::Longest, [4, 0, 0]);
+        // Unpublished blocks never become the head
+        assert_eq!(tree.head(), Some(a));
+        // `b` ties `a` on height, and the block seen first keeps the head
+        tree.publish(b);
+        assert_eq!(tree.head(), Some(a));
+        tree.publish(c);
+        assert_eq!(tree.head(), Some(c));
+        assert_eq!(tree.common_ancestor(a, c), Some(0));
+        assert_eq!(tree.path(tree.head()), vec![0, b, c]);
+    }
+
+    #[test]
+    fn most_votes_prefers_the_branch_with_more_votes() {
+        let (mut tree, [a, _, c]) = fork(ForkChoice::MostVotes, [4, 1, 1]);
+        tree.publish(c);
+        // The higher branch carries fewer votes
+        assert_eq!(tree.head(), Some(a));
+
+        let (mut tree, [_, _, c]) = fork(ForkChoice::MostVotes, [4, 2, 2]);
+        tree.publish(c);
+        // Equal votes fall back to height
+        assert_eq!(tree.head(), Some(c));
+    }
+
+    #[test]
+    fn confirmations_follow_the_branch() {
+        let (mut tree, [a, b, c]) = fork(ForkChoice::Longest, [4, 0, 0]);
+        tree.publish(c);
+        // Blob 2 only confirms on `a`'s branch, blob 3 on the other
+        assert_eq!(tree.confirmations(c, 2), vec![(3, false)]);
+        assert_eq!(
+            tree.confirmed_view(Some(a), 1),
+            HashMap::from([(1, false), (2, true)])
+        );
+        assert_eq!(
+            tree.confirmed_view(Some(c), 2),
+            HashMap::from([(1, false), (3, false)])
+        );
+        assert_eq!(tree.confirmations(b, 3), Vec::new());
+    }
+}
@@ -0,0 +1,274 @@
+use super::blob_queue::BlobQueue;
+use super::vote_gate::VoteGate;
+use crate::models::{
+    Blob, Block, BufferedVote, CongestionMetrics, InclusionPolicy, Settings, SimulationResult,
+    VoteMessage, VoteRecord,
+};
+use crate::simulation::Scenario;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use std::collections::{HashMap, HashSet};
+use tracing::{debug, info};
+
+// A cast vote waiting in the pool for a proposer with room for it
+struct PooledVote {
+    voter: usize,
+    blob_id: usize,
+    honest: bool,
+    cast_at: usize,
+    // Position in the committee of the block it was cast in
+    position: usize,
+    // Index into the run's vote records
+    record: usize,
+}
+
+// Orders the pool by `policy`; ties keep the pool's arrival order
+fn order_pool(pool: &mut [PooledVote], policy: InclusionPolicy, blobs: &HashMap<usize, Blob>) {
+    match policy {
+        InclusionPolicy::Fifo => pool.sort_by_key(|vote| vote.cast_at),
+        InclusionPolicy::OldestBlobFirst => {
+            pool.sort_by_key(|vote| (blobs[&vote.blob_id].included_at, vote.blob_id))
+        }
+        InclusionPolicy::ClosestToThreshold => pool.sort_by_key(|vote| {
+            let blob = &blobs[&vote.blob_id];
+            (
+                blob.votes_honest.abs_diff(blob.votes_malicious),
+                vote.blob_id,
+            )
+        }),
+        InclusionPolicy::Adversarial => pool.sort_by_key(|vote| vote.honest),
+    }
+}
+
+// Every committee member votes on every open blob, honest nodes Yes and malicious
+// nodes No, and the votes join a pool. Each proposer takes up to the block limit
+// from the pool in the order of its policy; the rest wait for the next block until
+// their blob confirms. Malicious proposers censor nothing outright, they only order
+// the pool, so honest votes are dropped only when the limit binds.
+pub(crate) fn run(
+    config: &Settings,
+    rng: &mut StdRng,
+    on_block: &mut dyn FnMut(&Block),
+) -> SimulationResult {
+    let honest_nodes: HashSet<usize> = (0..(config.total_nodes - config.malicious_nodes)).collect();
+    let all_nodes: Vec<usize> = (0..config.total_nodes).collect();
+    let settings = &config.block_limit;
+
+    let mut metrics = CongestionMetrics {
+        honest_policy: settings.honest_policy.name().to_string(),
+        malicious_policy: settings.malicious_policy.name().to_string(),
+        ..Default::default()
+    };
+    let mut pool: Vec<PooledVote> = Vec::new();
+    let mut honest_delays: Vec<usize> = Vec::new();
+    let mut malicious_delays: Vec<usize> = Vec::new();
+
+    let mut blobs: HashMap<usize, Blob> = HashMap::new();
+    let mut blob_queue = BlobQueue::new(config);
+    let mut gate = VoteGate::new(config);
+    let mut blocks: Vec<Block> = Vec::new();
+    let mut vote_records: Vec<VoteRecord> = Vec::new();
+
+    for block in 1..=config.total_blocks {
+        let block_proposer = *all_nodes.choose(rng).unwrap();
+        let proposer_honest = honest_nodes.contains(&block_proposer);
+
+        blob_queue.arrive(block, rng, &mut blobs);
+        let included_blobs = blob_queue.include(block, &mut blobs);
+
+        let selected_nodes: Vec<usize> = all_nodes
+            .choose_multiple(rng, config.nodes_per_block)
+            .cloned()
+            .collect();
+        gate.open_round(block, &selected_nodes);
+
+        // This committee's votes join the pool behind everything still waiting
+        let mut block_votes: HashMap<usize, Vec<Option<bool>>> = HashMap::new();
+        for &blob_id in blob_queue.unconfirmed() {
+            for (position, &node) in selected_nodes.iter().enumerate() {
+                let honest = honest_nodes.contains(&node);
+                pool.push(PooledVote {
+                    voter: node,
+                    blob_id,
+                    honest,
+                    cast_at: block,
+                    position,
+                    record: vote_records.len(),
+                });
+                vote_records.push(VoteRecord::censored(
+                    node,
+                    blob_id,
+                    honest,
+                    honest,
+                    block,
+                    proposer_honest,
+                ));
+            }
+            block_votes.insert(blob_id, vec![None; selected_nodes.len()]);
+        }
+
+        let payload_bytes: usize = included_blobs.iter().map(|id| blobs[id].size).sum();
+        let capacity = settings.vote_capacity(payload_bytes, &config.bandwidth);
+        let (policy, inclusion) = if proposer_honest {
+            (settings.honest_policy, &mut metrics.honest_proposers)
+        } else {
+            (settings.malicious_policy, &mut metrics.malicious_proposers)
+        };
+        inclusion.blocks += 1;
+        if pool.len() > capacity {
+            inclusion.congested_blocks += 1;
+            order_pool(&mut pool, policy, &blobs);
+        }
+
+        let mut buffered_votes: HashMap<usize, Vec<BufferedVote>> = HashMap::new();
+        let count = capacity.min(pool.len());
+        for vote in pool.drain(..count) {
+            let blob = blobs.get_mut(&vote.blob_id).unwrap();
+            let message = VoteMessage::sign(vote.voter, vote.blob_id, vote.cast_at, vote.honest);
+            if gate.admit(&message, blob, block).is_err() {
+                continue;
+            }
+            if vote.honest {
+                blob.votes_honest += 1;
+                inclusion.included_honest += 1;
+                honest_delays.push(block - vote.cast_at);
+            } else {
+                blob.votes_malicious += 1;
+                inclusion.included_malicious += 1;
+                malicious_delays.push(block - vote.cast_at);
+            }
+            vote_records[vote.record].included_at = Some(block);
+            if vote.cast_at == block {
+                block_votes.get_mut(&vote.blob_id).unwrap()[vote.position] = Some(vote.honest);
+            } else {
+                buffered_votes
+                    .entry(vote.blob_id)
+                    .or_default()
+                    .push(BufferedVote {
+                        voter: vote.voter,
+                        cast_at: vote.cast_at,
+                        decision: vote.honest,
+                    });
+            }
+        }
+        for vote in &pool {
+            if vote.honest {
+                inclusion.left_out_honest += 1;
+            } else {
+                inclusion.left_out_malicious += 1;
+            }
+        }
+        // Later proposers see the pool in arrival order again
+        pool.sort_by_key(|vote| vote.record);
+
+        let block = Block {
+            number: block,
+            proposer: block_proposer,
+            blobs: included_blobs,
+            selected_nodes,
+            votes: block_votes,
+            buffered_votes,
+            header: None,
+        };
+
+        gate.attack(&block, &blobs, rng);
+        for confirmed_blob_id in blob_queue.confirm(block.number, &mut blobs) {
+            // Votes still pooled for a confirmed blob can never count
+            pool.retain(|vote| {
+                if vote.blob_id != confirmed_blob_id {
+                    return true;
+                }
+                if vote.honest {
+                    metrics.lost_honest += 1;
+                } else {
+                    metrics.lost_malicious += 1;
+                }
+                false
+            });
+        }
+        debug!(
+            block = block.number,
+            proposer = block.proposer,
+            capacity,
+            pooled = pool.len(),
+            "block produced"
+        );
+        on_block(&block);
+        blocks.push(block);
+    }
+    metrics.pending_votes = pool.len();
+    metrics.honest_delays = honest_delays;
+    metrics.malicious_delays = malicious_delays;
+
+    info!(
+        congested_blocks = metrics.honest_proposers.congested_blocks
+            + metrics.malicious_proposers.congested_blocks,
+        lost_honest = metrics.lost_honest,
+        lost_malicious = metrics.lost_malicious,
+        pending_votes = metrics.pending_votes,
+        "congestion totals"
+    );
+
+    SimulationResult {
+        scenario: Scenario::Congestion,
+        settings: config.clone(),
+        honest_nodes,
+        blocks,
+        blobs,
+        votes: vote_records,
+        censorship: None,
+        das: None,
+        selective: None,
+        adversary: None,
+        fork: None,
+        equivocation: None,
+        congestion: Some(metrics),
+        spam: None,
+        fees: None,
+        phases: Vec::new(),
+        recovery: None,
+        vote_validation: Some(gate.finish()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn policies_order_the_pool() {
+        // Blob 0 was included first and is far from its threshold, blob 1 is tied
+        let mut blobs = HashMap::from([(0, Blob::new(0, 1, 0)), (1, Blob::new(1, 1, 0))]);
+        let blob = blobs.get_mut(&0).unwrap();
+        blob.included_at = Some(1);
+        blob.votes_honest = 3;
+        let blob = blobs.get_mut(&1).unwrap();
+        blob.included_at = Some(2);
+        blob.votes_honest = 2;
+        blob.votes_malicious = 2;
+
+        // Blob, honesty and block cast in, in order of arrival
+        let votes = [(1, true, 2), (0, false, 2), (1, false, 3), (0, true, 3)];
+        let order = |policy| {
+            let mut pool: Vec<PooledVote> = votes
+                .iter()
+                .enumerate()
+                .map(|(record, &(blob_id, honest, cast_at))| PooledVote {
+                    voter: record,
+                    blob_id,
+                    honest,
+                    cast_at,
+                    position: 0,
+                    record,
+                })
+                .collect();
+            order_pool(&mut pool, policy, &blobs);
+            pool.iter().map(|vote| vote.record).collect::<Vec<_>>()
+        };
+
+        assert_eq!(order(InclusionPolicy::Fifo), vec![0, 1, 2, 3]);
+        assert_eq!(order(InclusionPolicy::OldestBlobFirst), vec![1, 3, 0, 2]);
+        assert_eq!(order(InclusionPolicy::ClosestToThreshold), vec![0, 2, 1, 3]);
+        assert_eq!(order(InclusionPolicy::Adversarial), vec![1, 2, 0, 3]);
+    }
+}
@@ -0,0 +1,256 @@
+use crate::models::{Blob, DasLayout, DasMetrics, DasSettings};
+use rand::rngs::StdRng;
+use rand::seq::index;
+use std::collections::{HashMap, HashSet};
+
+// Erasure-coded blobs with chunk-level sampling by committee members. Chunks served
+// to honest samplers are kept so the network's ability to reconstruct attacked
+// blobs can be checked at the end of the run.
+pub(crate) struct Sampler {
+    layout: DasLayout,
+    chunks: usize,
+    data_chunks: usize,
+    samples: usize,
+    withheld: usize,
+    // Withheld chunks given explicitly; otherwise the minimal unrecoverable pattern is used
+    uniform_withholding: bool,
+    // Chunks honest nodes hold, per blob
+    held: HashMap<usize, Vec<bool>>,
+    // Attacked blobs whose chunks the adversary serves again
+    released: HashSet<usize>,
+    metrics: DasMetrics,
+}
+
+impl Sampler {
+    pub(crate) fn new(das: &DasSettings) -> Self {
+        let chunks = das.total_chunks();
+        let samples = das.samples.min(chunks);
+        let withheld = das.withheld_chunks();
+        Self {
+            layout: das.layout.clone(),
+            chunks,
+            data_chunks: das.data_chunks,
+            samples,
+            withheld,
+            uniform_withholding: das.withheld_chunks.is_some(),
+            held: HashMap::new(),
+            released: HashSet::new(),
+            metrics: DasMetrics {
+                chunks,
+                samples,
+                withheld_chunks: withheld,
+                ..Default::default()
+            },
+        }
+    }
+
+    // The adversary withholds a subset of the blob's chunks; a released blob has the
+    // same chunks withheld again
+    pub(crate) fn withhold(&mut self, blob: &mut Blob, rng: &mut StdRng) {
+        self.released.remove(&blob.id);
+        if !blob.withheld_chunks.is_empty() || self.withheld == 0 {
+            return;
+        }
+        let mut withheld = match self.layout {
+            DasLayout::TwoD { side } if !self.uniform_withholding => {
+                // Same rows and columns withheld, so none of them can be decoded
+                let lines = (side / 2 + 1).min(side);
+                let rows = index::sample(rng, side, lines);
+                let columns = index::sample(rng, side, lines);
+                rows.iter()
+                    .flat_map(|row| columns.iter().map(move |column| row * side + column))
+                    .collect()
+            }
+            _ => index::sample(rng, self.chunks, self.withheld).into_vec(),
+        };
+        withheld.sort_unstable();
+        blob.withheld_chunks = withheld;
+        self.metrics.attacked_blobs += 1;
+    }
+
+    // The adversary serves every withheld chunk from now on
+    pub(crate) fn release(&mut self, blob: &Blob) {
+        if !blob.withheld_chunks.is_empty() {
+            self.released.insert(blob.id);
+        }
+    }
+
+    // Withholds the blob's chunks in a block where the adversary attacks it and serves
+    // them again in any other block
+    pub(crate) fn serve(&mut self, blob: &mut Blob, withhold: bool, rng: &mut StdRng) {
+        if withhold {
+            self.withhold(blob, rng);
+        } else {
+            self.release(blob);
+        }
+    }
+
+    // An honest committee member samples distinct chunks and votes Yes only if all are served
+    pub(crate) fn vote(&mut self, blob: &Blob, rng: &mut StdRng) -> bool {
+        let held = self
+            .held
+            .entry(blob.id)
+            .or_insert_with(|| vec![false; self.chunks]);
+        let withheld = !blob.withheld_chunks.is_empty() && !self.released.contains(&blob.id);
+        let mut available = true;
+        for chunk in index::sample(rng, self.chunks, self.samples) {
+            if withheld && blob.withheld_chunks.binary_search(&chunk).is_ok() {
+                available = false;
+            } else {
+                held[chunk] = true;
+            }
+        }
+
+        if withheld {
+            self.metrics.sampled_votes += 1;
+            self.metrics.detections += usize::from(!available);
+        }
+        available
+    }
+
+    // Marks each attacked blob as recoverable or not and returns the run's metrics
+    pub(crate) fn finish(mut self, blobs: &mut HashMap<usize, Blob>) -> DasMetrics {
+        for blob in blobs.values_mut() {
+            if blob.withheld_chunks.is_empty() {
+                continue;
+            }
+            // Released blobs were published in full
+            let recoverable = self.released.contains(&blob.id)
+                || match self.held.get(&blob.id) {
+                    Some(held) => self.reconstruct(held.clone()),
+                    None => false,
+                };
+            blob.recoverable = Some(recoverable);
+            self.metrics.recoverable_blobs += usize::from(recoverable);
+        }
+        self.metrics
+    }
+
+    // Whether the chunks held by honest nodes are enough to rebuild the whole blob
+    fn reconstruct(&self, mut known: Vec<bool>) -> bool {
+        match self.layout {
+            DasLayout::OneD => known.iter().filter(|&&held| held).count() >= self.data_chunks,
+            DasLayout::TwoD { side } => {
+                // Decode any row or column with at least half of its chunks until nothing changes
+                let needed = side.div_ceil(2);
+                let mut progress = true;
+                while progress {
+                    progress = false;
+                    for line in 0..side {
+                        let row: Vec<usize> = (0..side).map(|i| line * side + i).collect();
+                        let column: Vec<usize> = (0..side).map(|i| i * side + line).collect();
+                        for cells in [row, column] {
+                            let held = cells.iter().filter(|&&cell| known[cell]).count();
+                            if held >= needed && held < side {
+                                cells.iter().for_each(|&cell| known[cell] = true);
+                                progress = true;
+                            }
+                        }
+                    }
+                }
+                known.iter().all(|&held| held)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    fn sampler(layout: DasLayout, chunks: usize, data_chunks: usize, samples: usize) -> Sampler {
+        Sampler::new(&DasSettings {
+            layout,
+            chunks,
+            data_chunks,
+            samples,
+            withheld_chunks: None,
+        })
+    }
+
+    #[test]
+    fn sampling_every_chunk_detects_withholding() {
+        // 8 chunks with 4 needed: the fewest to withhold is 5
+        let mut sampler = sampler(DasLayout::OneD, 8, 4, 8);
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut blobs = HashMap::from([(0, Blob::new(0, 1, 0))]);
+        let blob = blobs.get_mut(&0).unwrap();
+
+        sampler.withhold(blob, &mut rng);
+        assert_eq!(blob.withheld_chunks.len(), 5);
+        assert!(!sampler.vote(blob, &mut rng));
+
+        // The three served chunks honest samplers kept are too few to rebuild it
+        let metrics = sampler.finish(&mut blobs);
+        assert_eq!(blobs[&0].recoverable, Some(false));
+        assert_eq!(metrics.recoverable_blobs, 0);
+        assert_eq!(
+            (
+                metrics.attacked_blobs,
+                metrics.sampled_votes,
+                metrics.detections
+            ),
+            (1, 1, 1)
+        );
+    }
+
+    #[test]
+    fn two_d_withholding_blocks_rows_and_columns() {
+        // A 3 x 3 square of a 4 x 4 grid
+        let mut sampler = sampler(DasLayout::TwoD { side: 4 }, 0, 0, 16);
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut blob = Blob::new(0, 1, 0);
+
+        sampler.withhold(&mut blob, &mut rng);
+        assert_eq!(blob.withheld_chunks.len(), 9);
+        let rows: HashSet<usize> = blob.withheld_chunks.iter().map(|chunk| chunk / 4).collect();
+        let columns: HashSet<usize> = blob.withheld_chunks.iter().map(|chunk| chunk % 4).collect();
+        assert_eq!((rows.len(), columns.len()), (3, 3));
+    }
+
+    #[test]
+    fn two_d_reconstruction_decodes_rows_and_columns() {
+        let sampler = sampler(DasLayout::TwoD { side: 4 }, 0, 0, 1);
+
+        // Two full rows give every column half its chunks
+        let known: Vec<bool> = (0..16).map(|cell| cell < 8).collect();
+        assert!(sampler.reconstruct(known));
+
+        // All but a 3 x 3 square: each of its rows and columns holds one chunk
+        let known: Vec<bool> = (0..16).map(|cell| cell / 4 == 3 || cell % 4 == 3).collect();
+        assert!(!sampler.reconstruct(known));
+
+        // A diagonal gives each line one chunk, short of the two it needs
+        let known: Vec<bool> = (0..16).map(|cell| cell / 4 == cell % 4).collect();
+        assert!(!sampler.reconstruct(known));
+
+        // Two chunks in each of rows 0 and 1 decode those rows, then every column
+        let known: Vec<bool> = (0..16).map(|cell| [0, 1, 6, 7].contains(&cell)).collect();
+        assert!(sampler.reconstruct(known));
+    }
+
+    #[test]
+    fn one_d_reconstruction_needs_data_chunks() {
+        let sampler = sampler(DasLayout::OneD, 8, 4, 1);
+
+        assert!(sampler.reconstruct((0..8).map(|chunk| chunk < 4).collect()));
+        assert!(!sampler.reconstruct((0..8).map(|chunk| chunk < 3).collect()));
+    }
+
+    #[test]
+    fn released_blob_is_served_and_recoverable() {
+        let mut sampler = sampler(DasLayout::OneD, 8, 4, 8);
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut blobs = HashMap::from([(0, Blob::new(0, 1, 0))]);
+        let blob = blobs.get_mut(&0).unwrap();
+
+        sampler.serve(blob, true, &mut rng);
+        sampler.serve(blob, false, &mut rng);
+        assert!(sampler.vote(blob, &mut rng));
+
+        let metrics = sampler.finish(&mut blobs);
+        assert_eq!(blobs[&0].recoverable, Some(true));
+        assert_eq!(metrics.detections, 0);
+    }
+}
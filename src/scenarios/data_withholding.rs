@@ -1,83 +1,99 @@
+use super::blob_queue::BlobQueue;
+use super::das::Sampler;
+use super::schedule::attack_phases;
+use super::vote_gate::VoteGate;
+use crate::models::{Behavior, Blob, Block, Settings, SimulationResult, VoteMessage, VoteRecord};
+use crate::simulation::Scenario;
+use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
-use sim::{
-    models::{Blob, Block, Settings},
-    utils::csv_writer::create_results_csv,
-};
 use std::collections::{HashMap, HashSet};
-use std::fs::File;
-use std::io::BufWriter;
-
-pub fn run() {
-    let config = Settings::load("config");
-    let mut rng = rand::thread_rng();
+use tracing::debug;
 
+pub(crate) fn run(
+    config: &Settings,
+    rng: &mut StdRng,
+    on_block: &mut dyn FnMut(&Block),
+) -> SimulationResult {
     // Define our node groups
     let honest_nodes: HashSet<usize> = (0..(config.total_nodes - config.malicious_nodes)).collect();
-    let _malicious_nodes: HashSet<usize> =
-        ((config.total_nodes - config.malicious_nodes)..config.total_nodes).collect();
-    let all_nodes: HashSet<usize> = (0..config.total_nodes).collect();
+    let all_nodes: Vec<usize> = (0..config.total_nodes).collect();
 
     let mut blobs: HashMap<usize, Blob> = HashMap::new();
-    let mut unconfirmed_blobs: HashSet<usize> = HashSet::new();
-    let mut next_blob_id = 0;
+    let mut blob_queue = BlobQueue::new(config);
+    let mut gate = VoteGate::new(config);
     let mut blocks: Vec<Block> = Vec::new();
+    let mut vote_records: Vec<VoteRecord> = Vec::new();
 
     // Parameters for data withholding
     // E.g., last K_f blocks will be attack blocks (data withholding active)
     let attack_start_block = config.total_blocks.saturating_sub(config.k_f);
+    let mut sampler = Sampler::new(&config.das);
 
     for block_number in 1..=config.total_blocks {
-        let block_proposer = *all_nodes.iter().collect::<Vec<_>>().choose(&mut rng).unwrap();
+        let block_proposer = all_nodes.choose(rng).unwrap();
+        let proposer_honest = honest_nodes.contains(block_proposer);
 
-        // Create a blob for the new block and keep track of unconfirmed blobs
-        let new_blob = Blob::new(next_blob_id);
-        blobs.insert(next_blob_id, new_blob);
-        unconfirmed_blobs.insert(next_blob_id);
-        next_blob_id += 1;
+        blob_queue.arrive(block_number, rng, &mut blobs);
+        let included_blobs = blob_queue.include(block_number, &mut blobs);
 
         // Select the voter nodes to participate in this block
-        let selected_nodes: Vec<usize> = all_nodes.iter().cloned().collect::<Vec<_>>()
-            .choose_multiple(&mut rng, config.nodes_per_block)
+        let selected_nodes: Vec<usize> = all_nodes
+            .choose_multiple(rng, config.nodes_per_block)
             .cloned()
             .collect();
+        gate.open_round(block_number, &selected_nodes);
 
         // For each unconfirmed blob, simulate the vote
         let mut block_votes: HashMap<usize, Vec<Option<bool>>> = HashMap::new();
 
-        for &blob_id in &unconfirmed_blobs {
+        for &blob_id in blob_queue.unconfirmed() {
             let mut votes = Vec::new();
 
             // Check if this block falls into the data withholding phase
             let data_withholding_active = block_number >= attack_start_block;
-            
+
+            // Once the attack starts the adversary withholds chunks of every open blob
+            if data_withholding_active {
+                sampler.withhold(blobs.get_mut(&blob_id).unwrap(), rng);
+            }
+
             for &node in &selected_nodes {
+                let blob = blobs.get_mut(&blob_id).unwrap();
                 // For honest nodes:
-                if honest_nodes.contains(&node) {
-                    if data_withholding_active {
-                        // Data is withheld, so honest nodes cannot verify the blob sample.
-                        // They vote "No" (or you could decide None to simulate an abstention).
-                        votes.push(Some(false));
-                        // Optionally update the blob counters if you track failure votes
-                        blobs.get_mut(&blob_id).unwrap().votes_honest += 0;
-                    } else {
-                        // Normal behavior: download sample and verify, then vote Yes
-                        votes.push(Some(true));
-                        blobs.get_mut(&blob_id).unwrap().votes_honest += 1;
-                    }
+                let decision = if honest_nodes.contains(&node) {
+                    // Sample chunks and vote Yes only if every sample is served
+                    sampler.vote(blob, rng)
                 } else {
                     // For malicious nodes, choose their behavior arbitrarily.
                     // They might vote "Yes" in attack blocks to artificially help their chain.
-                    let vote = if data_withholding_active {
-                        // Attack behavior: may vote Yes even if withholding data
-                        Some(true)
-                    } else {
-                        // Normal malicious strategy; in a real simulation, the choice could be random.
-                        Some(false)
-                    };
-                    votes.push(vote);
-                    if vote == Some(false) {
-                        blobs.get_mut(&blob_id).unwrap().votes_malicious += 1;
+                    // Otherwise the normal malicious strategy; in a real simulation, the
+                    // choice could be random.
+                    data_withholding_active
+                };
+                let message = VoteMessage::sign(node, blob_id, block_number, decision);
+                if gate.admit(&message, blob, block_number).is_err() {
+                    votes.push(None);
+                    continue;
+                }
+                if honest_nodes.contains(&node) {
+                    if decision {
+                        blob.votes_honest += 1;
                     }
+                } else if !decision {
+                    blob.votes_malicious += 1;
+                }
+                votes.push(Some(decision));
+            }
+            for (&node, vote) in selected_nodes.iter().zip(&votes) {
+                if let Some(decision) = *vote {
+                    vote_records.push(VoteRecord::included(
+                        node,
+                        blob_id,
+                        decision,
+                        honest_nodes.contains(&node),
+                        block_number,
+                        proposer_honest,
+                    ));
                 }
             }
             block_votes.insert(blob_id, votes);
@@ -87,29 +103,53 @@ pub fn run() {
         let block = Block {
             number: block_number,
             proposer: *block_proposer,
+            blobs: included_blobs,
             selected_nodes: selected_nodes.clone(),
             votes: block_votes,
             buffered_votes: HashMap::new(),
+            header: None,
         };
 
+        gate.attack(&block, &blobs, rng);
         // Confirm blobs when they are k blocks deep
-        if block.number >= config.confirmation_depth {
-            let confirmed_blob_id = block.number - config.confirmation_depth;
-            if let Some(blob) = blobs.get_mut(&confirmed_blob_id) {
-                blob.is_confirmed = true;
-                unconfirmed_blobs.remove(&confirmed_blob_id);
-            }
-        }
+        blob_queue.confirm(block.number, &mut blobs);
 
-        println!("Block Number: {}", block.number);
+        debug!(
+            block = block.number,
+            proposer = block.proposer,
+            "block produced"
+        );
+        on_block(&block);
         blocks.push(block);
     }
 
-    // Write out the simulation results
-    let file = File::create("simulation_results_data_withholding.csv").expect("Unable to create file");
-    let mut writer = BufWriter::new(file);
-    create_results_csv(&blocks, &honest_nodes, &mut writer).expect("Unable to write CSV");
+    // Whether honest nodes could rebuild each attacked blob from what they sampled
+    let das_metrics = sampler.finish(&mut blobs);
 
-    // Optionally, you could also write the table summaries similar to the other scenarios
-    println!("Simulation complete. Results written to 'simulation_results_data_withholding.csv'.");
-} 
\ No newline at end of file
+    SimulationResult {
+        scenario: Scenario::DataWithholding,
+        settings: config.clone(),
+        honest_nodes,
+        blocks,
+        blobs,
+        votes: vote_records,
+        censorship: None,
+        das: Some(das_metrics),
+        selective: None,
+        adversary: None,
+        fork: None,
+        equivocation: None,
+        congestion: None,
+        spam: None,
+        fees: None,
+        phases: attack_phases(
+            config.total_blocks,
+            attack_start_block,
+            config.total_blocks,
+            "withhold",
+            vec![Behavior::Withhold],
+        ),
+        recovery: None,
+        vote_validation: Some(gate.finish()),
+    }
+}
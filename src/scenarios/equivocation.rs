@@ -0,0 +1,222 @@
+use super::blob_queue::BlobQueue;
+use super::stake::node_weights;
+use super::vote_gate::VoteGate;
+use crate::models::{
+    Blob, Block, EquivocationMetrics, Settings, SimulationResult, SlashingPenalty, VoteMessage,
+    VoteRecord,
+};
+use crate::simulation::Scenario;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use tracing::{debug, info};
+
+// A double vote waiting for the next proposer
+struct ConflictingVote {
+    voter: usize,
+    blob_id: usize,
+    cast_at: usize,
+}
+
+// Adversary share of the weight of nodes still eligible for committees
+fn effective_power(weights: &[f64], eligible: &[bool], honest_nodes: &HashSet<usize>) -> f64 {
+    let (malicious, total) = (0..weights.len()).filter(|&node| eligible[node]).fold(
+        (0.0, 0.0),
+        |(malicious, total), node| {
+            let weight = weights[node];
+            if honest_nodes.contains(&node) {
+                (malicious, total + weight)
+            } else {
+                (malicious + weight, total + weight)
+            }
+        },
+    );
+    if total == 0.0 {
+        0.0
+    } else {
+        malicious / total
+    }
+}
+
+// Committee members are drawn in proportion to their weight. Malicious members vote No in their block and, with probability
+// `equivocation.rate` per open blob, send a conflicting Yes for the same blob to the
+// next proposer. An honest next proposer finds it contradicts the vote on chain,
+// which proves the equivocation, and the voter is slashed at the end of that
+// block; a malicious one drops it. Only the first vote counts towards the tally.
+pub(crate) fn run(
+    config: &Settings,
+    rng: &mut StdRng,
+    on_block: &mut dyn FnMut(&Block),
+) -> SimulationResult {
+    let honest_nodes: HashSet<usize> = (0..(config.total_nodes - config.malicious_nodes)).collect();
+    let all_nodes: Vec<usize> = (0..config.total_nodes).collect();
+    let settings = &config.equivocation;
+
+    let mut weights = node_weights(config, rng);
+    let mut eligible = vec![true; config.total_nodes];
+    let mut rewards = vec![0.0; config.total_nodes];
+    let mut slashed: HashSet<usize> = HashSet::new();
+    let mut metrics = EquivocationMetrics {
+        penalty: settings.penalty.name().to_string(),
+        initial_power: effective_power(&weights, &eligible, &honest_nodes),
+        ..Default::default()
+    };
+
+    let mut blobs: HashMap<usize, Blob> = HashMap::new();
+    let mut blob_queue = BlobQueue::new(config);
+    let mut gate = VoteGate::new(config);
+    let mut blocks: Vec<Block> = Vec::new();
+    let mut vote_records: Vec<VoteRecord> = Vec::new();
+    let mut conflicting: Vec<ConflictingVote> = Vec::new();
+
+    for block in 1..=config.total_blocks {
+        let block_proposer = *all_nodes.choose(rng).unwrap();
+        let proposer_honest = honest_nodes.contains(&block_proposer);
+
+        blob_queue.arrive(block, rng, &mut blobs);
+        let included_blobs = blob_queue.include(block, &mut blobs);
+
+        // Committees are drawn by weight, so a reduced weight makes a node less
+        // likely to be selected; nodes removed by slashing are never selected again
+        let candidates: Vec<usize> = all_nodes
+            .iter()
+            .copied()
+            .filter(|&node| eligible[node])
+            .collect();
+        let selected_nodes: Vec<usize> = candidates
+            .choose_multiple_weighted(rng, config.nodes_per_block, |&node| weights[node])
+            .unwrap()
+            .cloned()
+            .collect();
+        gate.open_round(block, &selected_nodes);
+
+        // Double votes sent during the last block reach this proposer. An honest one
+        // checks them against the votes already on chain without offering them for
+        // inclusion, and one that contradicts its voter's earlier vote proves the
+        // equivocation.
+        let mut offenders: BTreeSet<usize> = BTreeSet::new();
+        for vote in conflicting.drain(..) {
+            if !proposer_honest {
+                metrics.hidden += 1;
+                continue;
+            }
+            let message = VoteMessage::sign(vote.voter, vote.blob_id, vote.cast_at, true);
+            if gate.conflicts(&message) {
+                metrics.detected += 1;
+                offenders.insert(vote.voter);
+            }
+        }
+
+        let mut block_votes: HashMap<usize, Vec<Option<bool>>> = HashMap::new();
+        for &blob_id in blob_queue.unconfirmed() {
+            let blob = blobs.get_mut(&blob_id).unwrap();
+            let mut votes = Vec::with_capacity(selected_nodes.len());
+            for &node in &selected_nodes {
+                let honest = honest_nodes.contains(&node);
+                let message = VoteMessage::sign(node, blob_id, block, honest);
+                if gate.admit(&message, blob, block).is_err() {
+                    votes.push(None);
+                    continue;
+                }
+                if honest {
+                    blob.votes_honest += 1;
+                } else {
+                    blob.votes_malicious += 1;
+                }
+                rewards[node] += settings.vote_reward;
+                vote_records.push(VoteRecord::included(
+                    node,
+                    blob_id,
+                    honest,
+                    honest,
+                    block,
+                    proposer_honest,
+                ));
+                if !honest && rng.gen_bool(settings.rate) {
+                    metrics.equivocations += 1;
+                    conflicting.push(ConflictingVote {
+                        voter: node,
+                        blob_id,
+                        cast_at: block,
+                    });
+                    vote_records.push(VoteRecord::equivocation(
+                        node,
+                        blob_id,
+                        true,
+                        block,
+                        proposer_honest,
+                    ));
+                }
+                votes.push(Some(honest));
+            }
+            block_votes.insert(blob_id, votes);
+        }
+
+        for node in offenders {
+            match settings.penalty {
+                SlashingPenalty::Removal => eligible[node] = false,
+                SlashingPenalty::WeightReduction { factor } => weights[node] *= factor,
+                SlashingPenalty::RewardLoss { fraction } => {
+                    let lost = rewards[node] * fraction;
+                    rewards[node] -= lost;
+                    metrics.rewards_lost += lost;
+                }
+            }
+            slashed.insert(node);
+            debug!(block, node, "equivocation slashed");
+        }
+        metrics
+            .effective_power
+            .push(effective_power(&weights, &eligible, &honest_nodes));
+        metrics.slashed_by_block.push(slashed.len());
+
+        let block = Block {
+            number: block,
+            proposer: block_proposer,
+            blobs: included_blobs,
+            selected_nodes,
+            votes: block_votes,
+            buffered_votes: HashMap::new(),
+            header: None,
+        };
+
+        gate.attack(&block, &blobs, rng);
+        blob_queue.confirm(block.number, &mut blobs);
+        on_block(&block);
+        blocks.push(block);
+    }
+
+    metrics.slashed_nodes = slashed.len();
+    metrics.adversary_rewards = (0..config.total_nodes)
+        .filter(|node| !honest_nodes.contains(node))
+        .map(|node| rewards[node])
+        .sum();
+    info!(
+        equivocations = metrics.equivocations,
+        detected = metrics.detected,
+        slashed_nodes = metrics.slashed_nodes,
+        "equivocation totals"
+    );
+
+    SimulationResult {
+        scenario: Scenario::Equivocation,
+        settings: config.clone(),
+        honest_nodes,
+        blocks,
+        blobs,
+        votes: vote_records,
+        censorship: None,
+        das: None,
+        selective: None,
+        adversary: None,
+        fork: None,
+        equivocation: Some(metrics),
+        congestion: None,
+        spam: None,
+        fees: None,
+        phases: Vec::new(),
+        recovery: None,
+        vote_validation: Some(gate.finish()),
+    }
+}
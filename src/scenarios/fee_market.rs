@@ -0,0 +1,229 @@
+use super::blob_queue::BlobQueue;
+use super::vote_gate::VoteGate;
+use crate::models::{
+    Blob, Block, FeeMarketSettings, FeeMetrics, FeePolicy, FeeRecord, Settings, SimulationResult,
+    VoteMessage, VoteRecord,
+};
+use crate::simulation::Scenario;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::collections::{HashMap, HashSet};
+use tracing::{debug, info};
+
+// Tip the proposer receives for `blob` at `base_fee`, capped by the blob's max fee
+fn effective_tip(blob: &Blob, base_fee: f64) -> f64 {
+    blob.tip.min(blob.max_fee - base_fee)
+}
+
+// The base fee rises after blocks above the target and falls after those below, by
+// at most `max_change` and never below `min_base_fee`
+fn next_base_fee(base_fee: f64, included: usize, settings: &FeeMarketSettings) -> f64 {
+    let target = settings.target as f64;
+    // Blocks with no blob limit can hold more than twice the target
+    let included = included.min(2 * settings.target) as f64;
+    let base_fee = base_fee * (1.0 + settings.max_change * (included - target) / target);
+    base_fee.max(settings.min_base_fee)
+}
+
+// Every blob arrives with a max fee and a tip. Proposers take blobs whose max fee
+// covers the base fee, in the order of `fee_market.policy`, up to the block's
+// capacity; the base fee is burned and the proposer keeps the tips. After each block
+// the base fee moves towards `fee_market.target` blobs per block. Malicious
+// proposers skip a `censored_share` of blobs at the cost of their tips; honest
+// proposers include them.
+pub(crate) fn run(
+    config: &Settings,
+    rng: &mut StdRng,
+    on_block: &mut dyn FnMut(&Block),
+) -> SimulationResult {
+    let honest_nodes: HashSet<usize> = (0..(config.total_nodes - config.malicious_nodes)).collect();
+    let all_nodes: Vec<usize> = (0..config.total_nodes).collect();
+    let settings = &config.fee_market;
+    let capacity = config.max_blobs_per_block.unwrap_or(2 * settings.target);
+
+    let mut metrics = FeeMetrics {
+        policy: settings.policy.name().to_string(),
+        ..Default::default()
+    };
+    let mut censored: HashSet<usize> = HashSet::new();
+    let mut base_fee = settings.initial_base_fee.max(settings.min_base_fee);
+
+    let mut blobs: HashMap<usize, Blob> = HashMap::new();
+    let mut blob_queue = BlobQueue::new(config).with_capacity(capacity);
+    let mut gate = VoteGate::new(config);
+    let mut blocks: Vec<Block> = Vec::new();
+    let mut vote_records: Vec<VoteRecord> = Vec::new();
+
+    for block in 1..=config.total_blocks {
+        let block_proposer = *all_nodes.choose(rng).unwrap();
+        let proposer_honest = honest_nodes.contains(&block_proposer);
+
+        for blob_id in blob_queue.arrive(block, rng, &mut blobs) {
+            let blob = blobs.get_mut(&blob_id).unwrap();
+            blob.max_fee = rng.gen_range(settings.max_fee.min..=settings.max_fee.max);
+            blob.tip = rng.gen_range(settings.tip.min..=settings.tip.max);
+            if rng.gen_bool(settings.censored_share) {
+                censored.insert(blob_id);
+                metrics.censored_blobs.push(blob_id);
+            }
+        }
+
+        // Blobs the submitters will pay for at this base fee, in the policy's order
+        let mut candidates: Vec<usize> = blob_queue
+            .pending()
+            .filter(|blob_id| blobs[blob_id].max_fee >= base_fee)
+            .collect();
+        if settings.policy == FeePolicy::MaxRevenue {
+            candidates.sort_by(|a, b| {
+                effective_tip(&blobs[b], base_fee).total_cmp(&effective_tip(&blobs[a], base_fee))
+            });
+        }
+        let revenue = |chosen: &[usize]| -> f64 {
+            chosen
+                .iter()
+                .map(|blob_id| effective_tip(&blobs[blob_id], base_fee))
+                .fold(0.0, |sum, tip| sum + tip)
+        };
+        let uncensored = blob_queue.fit(candidates.iter().copied(), &blobs);
+        let chosen: Vec<usize> = if proposer_honest {
+            uncensored
+        } else {
+            let chosen = blob_queue.fit(
+                candidates
+                    .iter()
+                    .copied()
+                    .filter(|blob_id| !censored.contains(blob_id)),
+                &blobs,
+            );
+            metrics.forgone_revenue += revenue(&uncensored) - revenue(&chosen);
+            chosen
+        };
+
+        let mut record = FeeRecord {
+            base_fee,
+            included: chosen.len(),
+            revenue: revenue(&chosen),
+            burned: base_fee * chosen.len() as f64,
+            proposer_honest,
+            ..Default::default()
+        };
+        for &blob_id in &chosen {
+            let blob = blobs.get_mut(&blob_id).unwrap();
+            blob.fee_paid = Some(base_fee + effective_tip(blob, base_fee));
+        }
+        let included = blob_queue.include_chosen(block, &chosen, &mut blobs);
+        debug_assert_eq!(included, chosen);
+        record.backlog = blob_queue.pending().count();
+
+        let selected_nodes: Vec<usize> = all_nodes
+            .choose_multiple(rng, config.nodes_per_block)
+            .cloned()
+            .collect();
+        gate.open_round(block, &selected_nodes);
+
+        let mut block_votes: HashMap<usize, Vec<Option<bool>>> = HashMap::new();
+        for &blob_id in blob_queue.unconfirmed() {
+            let blob = blobs.get_mut(&blob_id).unwrap();
+            let mut votes = Vec::with_capacity(selected_nodes.len());
+            for &node in &selected_nodes {
+                let honest = honest_nodes.contains(&node);
+                let message = VoteMessage::sign(node, blob_id, block, honest);
+                if gate.admit(&message, blob, block).is_err() {
+                    votes.push(None);
+                    continue;
+                }
+                if honest {
+                    blob.votes_honest += 1;
+                } else {
+                    blob.votes_malicious += 1;
+                }
+                vote_records.push(VoteRecord::included(
+                    node,
+                    blob_id,
+                    honest,
+                    honest,
+                    block,
+                    proposer_honest,
+                ));
+                votes.push(Some(honest));
+            }
+            block_votes.insert(blob_id, votes);
+        }
+
+        let block = Block {
+            number: block,
+            proposer: block_proposer,
+            blobs: chosen,
+            selected_nodes,
+            votes: block_votes,
+            buffered_votes: HashMap::new(),
+            header: None,
+        };
+
+        base_fee = next_base_fee(base_fee, record.included, settings);
+
+        gate.attack(&block, &blobs, rng);
+        blob_queue.confirm(block.number, &mut blobs);
+        debug!(
+            block = block.number,
+            base_fee = record.base_fee,
+            included = record.included,
+            backlog = record.backlog,
+            "block produced"
+        );
+        metrics.blocks.push(record);
+        on_block(&block);
+        blocks.push(block);
+    }
+    metrics.final_base_fee = base_fee;
+
+    info!(
+        final_base_fee = metrics.final_base_fee,
+        honest_revenue = metrics.revenue(true),
+        malicious_revenue = metrics.revenue(false),
+        forgone_revenue = metrics.forgone_revenue,
+        "fee market totals"
+    );
+
+    SimulationResult {
+        scenario: Scenario::FeeMarket,
+        settings: config.clone(),
+        honest_nodes,
+        blocks,
+        blobs,
+        votes: vote_records,
+        censorship: None,
+        das: None,
+        selective: None,
+        adversary: None,
+        fork: None,
+        equivocation: None,
+        congestion: None,
+        spam: None,
+        fees: Some(metrics),
+        phases: Vec::new(),
+        recovery: None,
+        vote_validation: Some(gate.finish()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base_fee_moves_towards_target() {
+        // Target of 3 blobs and a 12.5% maximum change
+        let settings = FeeMarketSettings::default();
+
+        assert_eq!(next_base_fee(1.0, 3, &settings), 1.0);
+        assert_eq!(next_base_fee(1.0, 6, &settings), 1.125);
+        assert_eq!(next_base_fee(1.0, 0, &settings), 0.875);
+        assert_eq!(next_base_fee(1.0, 4, &settings), 1.0 + 0.125 / 3.0);
+        // Blocks beyond twice the target move it no further than a full one
+        assert_eq!(next_base_fee(1.0, 30, &settings), 1.125);
+        // Never below the 0.01 floor
+        assert_eq!(next_base_fee(0.011, 0, &settings), 0.01);
+    }
+}
@@ -1,4 +1,20 @@
 pub mod basic;
 pub mod vote_censorship;
 pub mod data_withholding;
-pub mod smart_data_withholding;
\ No newline at end of file
+pub mod smart_data_withholding;
+pub mod selective_withholding;
+pub mod scheduled;
+pub mod selfish_mining;
+pub mod equivocation;
+pub mod congestion;
+pub mod spam;
+pub mod fee_market;
+pub mod adversary;
+mod attack;
+mod blob_queue;
+mod chain;
+mod das;
+mod pending;
+mod schedule;
+mod stake;
+mod vote_gate;
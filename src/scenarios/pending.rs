@@ -0,0 +1,141 @@
+use super::vote_gate::VoteGate;
+use crate::models::{Blob, BufferedVote, CensorshipMetrics, VoteMessage, VoteRecord};
+use std::collections::HashMap;
+
+// A censored honest vote and its index into the run's vote records
+struct PendingVote {
+    blob_id: usize,
+    record: usize,
+}
+
+// Honest votes a malicious proposer left out of its block, waiting in the pool until
+// an honest proposer includes them or their blob confirms first
+#[derive(Default)]
+pub(crate) struct PendingVotes {
+    votes: Vec<PendingVote>,
+}
+
+impl PendingVotes {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    // Queues a censored vote and adds its record to the run's vote records
+    pub(crate) fn push(&mut self, record: VoteRecord, vote_records: &mut Vec<VoteRecord>) {
+        self.votes.push(PendingVote {
+            blob_id: record.blob_id,
+            record: vote_records.len(),
+        });
+        vote_records.push(record);
+    }
+
+    // An honest proposer of `block` includes every pending vote exactly once, as
+    // buffered votes of that block. Each still has to pass the gate before it counts.
+    pub(crate) fn drain_into(
+        &mut self,
+        block: usize,
+        gate: &mut VoteGate,
+        blobs: &mut HashMap<usize, Blob>,
+        vote_records: &mut [VoteRecord],
+        metrics: &mut CensorshipMetrics,
+    ) -> HashMap<usize, Vec<BufferedVote>> {
+        let mut buffered_votes: HashMap<usize, Vec<BufferedVote>> = HashMap::new();
+        for pending in self.votes.drain(..) {
+            let record = &mut vote_records[pending.record];
+            let blob = blobs.get_mut(&pending.blob_id).unwrap();
+            let message = VoteMessage::sign(
+                record.voter,
+                record.blob_id,
+                record.cast_at,
+                record.decision,
+            );
+            if gate.admit(&message, blob, block).is_err() {
+                continue;
+            }
+            if record.decision {
+                blob.votes_honest += 1;
+            }
+            buffered_votes
+                .entry(pending.blob_id)
+                .or_default()
+                .push(BufferedVote {
+                    voter: record.voter,
+                    cast_at: record.cast_at,
+                    decision: record.decision,
+                });
+            metrics.included_votes += 1;
+            metrics.inclusion_delays.push(block - record.cast_at);
+            record.included_at = Some(block);
+        }
+        buffered_votes
+    }
+
+    // Votes still pending for a confirmed blob can never count; returns how many
+    // were dropped
+    pub(crate) fn drop_confirmed(&mut self, confirmed: &[usize]) -> usize {
+        let before = self.votes.len();
+        self.votes
+            .retain(|pending| !confirmed.contains(&pending.blob_id));
+        before - self.votes.len()
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.votes.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Settings;
+
+    #[test]
+    fn honest_proposer_includes_pending_votes_once() {
+        let mut gate = VoteGate::new(&Settings::for_tests());
+        let mut blobs = HashMap::from([(0, Blob::new(0, 1, 0)), (1, Blob::new(1, 1, 0))]);
+        let mut vote_records = Vec::new();
+        let mut metrics = CensorshipMetrics::default();
+
+        // Block 2's committee votes, and its malicious proposer leaves the votes out
+        gate.open_round(2, &[0, 1]);
+        let mut pending = PendingVotes::new();
+        for (voter, blob_id, decision) in [(0, 0, true), (1, 0, false), (0, 1, true), (0, 0, true)]
+        {
+            let record = VoteRecord::censored(voter, blob_id, decision, true, 2, false);
+            pending.push(record, &mut vote_records);
+        }
+        assert_eq!((pending.len(), vote_records.len()), (4, 4));
+
+        // Blob 1 confirms before any honest proposer comes along
+        assert_eq!(pending.drop_confirmed(&[1]), 1);
+
+        gate.open_round(3, &[5, 6]);
+        let buffered =
+            pending.drain_into(3, &mut gate, &mut blobs, &mut vote_records, &mut metrics);
+        assert_eq!(pending.len(), 0);
+
+        // The replayed vote of node 0 is a duplicate and is neither included nor counted
+        let votes = |decisions: &[(usize, bool)]| -> Vec<BufferedVote> {
+            decisions
+                .iter()
+                .map(|&(voter, decision)| BufferedVote {
+                    voter,
+                    cast_at: 2,
+                    decision,
+                })
+                .collect()
+        };
+        assert_eq!(
+            buffered,
+            HashMap::from([(0, votes(&[(0, true), (1, false)]))])
+        );
+        assert_eq!(blobs[&0].votes_honest, 1);
+        assert_eq!(metrics.included_votes, 2);
+        assert_eq!(metrics.inclusion_delays, vec![1, 1]);
+        let included_at: Vec<Option<usize>> = vote_records
+            .iter()
+            .map(|record| record.included_at)
+            .collect();
+        assert_eq!(included_at, vec![Some(3), Some(3), None, None]);
+    }
+}
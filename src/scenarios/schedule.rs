@@ -0,0 +1,112 @@
+use crate::models::{AttackPhase, Behavior, PhaseRecord, StartCondition};
+
+// Walks an attack schedule block by block and records when each phase ran
+pub(crate) struct PhaseTracker<'a> {
+    phases: &'a [AttackPhase],
+    current: Option<usize>,
+    started_at: usize,
+    records: Vec<PhaseRecord>,
+}
+
+impl<'a> PhaseTracker<'a> {
+    pub(crate) fn new(phases: &'a [AttackPhase]) -> Self {
+        Self {
+            phases,
+            current: None,
+            started_at: 0,
+            records: Vec::new(),
+        }
+    }
+
+    // Moves to the phase active in `block` and returns its behaviors; blocks before
+    // the first phase starts have none
+    pub(crate) fn advance(
+        &mut self,
+        block: usize,
+        backlog: usize,
+        proposer_malicious: bool,
+    ) -> &'a [Behavior] {
+        // Several phases may start in the same block; only the last one runs
+        loop {
+            let next = self.current.map_or(0, |current| current + 1);
+            let Some(phase) = self.phases.get(next) else {
+                break;
+            };
+            let previous_ended = match self.current {
+                Some(current) => self.phases[current]
+                    .blocks
+                    .is_some_and(|blocks| block >= self.started_at + blocks),
+                // A first phase without a start of its own begins with the run
+                None => phase.start.is_none() && phase.start_when.is_none(),
+            };
+            let condition_met = match &phase.start_when {
+                Some(StartCondition::BacklogAbove { blobs }) => backlog > *blobs,
+                Some(StartCondition::MaliciousProposer) => proposer_malicious,
+                None => false,
+            };
+            let starts =
+                previous_ended || condition_met || phase.start.is_some_and(|start| block >= start);
+            if !starts {
+                break;
+            }
+
+            if self
+                .records
+                .last()
+                .is_some_and(|last| last.start_block == block)
+            {
+                self.records.pop();
+            }
+            self.current = Some(next);
+            self.started_at = block;
+            self.records.push(PhaseRecord {
+                name: phase.name.clone(),
+                behaviors: phase.behaviors.clone(),
+                start_block: block,
+                end_block: block,
+            });
+        }
+
+        match self.current {
+            Some(current) => {
+                self.records.last_mut().unwrap().end_block = block;
+                &self.phases[current].behaviors
+            }
+            None => &[],
+        }
+    }
+
+    pub(crate) fn finish(self) -> Vec<PhaseRecord> {
+        self.records
+    }
+}
+
+// Phases of a scenario with one fixed attack window, blocks `start..=end` of the run
+pub(crate) fn attack_phases(
+    total_blocks: usize,
+    start: usize,
+    end: usize,
+    name: &str,
+    behaviors: Vec<Behavior>,
+) -> Vec<PhaseRecord> {
+    let start = start.max(1);
+    let end = end.min(total_blocks);
+    let mut phases = Vec::new();
+    if start > end {
+        return phases;
+    }
+    let mut push = |name: &str, behaviors: Vec<Behavior>, start_block, end_block| {
+        if start_block <= end_block {
+            phases.push(PhaseRecord {
+                name: name.to_string(),
+                behaviors,
+                start_block,
+                end_block,
+            });
+        }
+    };
+    push("normal", Vec::new(), 1, start - 1);
+    push(name, behaviors, start, end);
+    push("recover", Vec::new(), end + 1, total_blocks);
+    phases
+}
@@ -0,0 +1,52 @@
+use super::adversary::Adversary;
+use super::attack::{Attack, AttackRun};
+use super::schedule::PhaseTracker;
+use crate::models::{Behavior, Block, Settings, SimulationResult};
+use crate::simulation::Scenario;
+use rand::rngs::StdRng;
+use tracing::{debug, info};
+
+// Runs the phases of `config.schedule` in order, applying the behaviors active in each
+pub(crate) fn run(
+    config: &Settings,
+    rng: &mut StdRng,
+    adversary: &mut dyn Adversary,
+    on_block: &mut dyn FnMut(&Block),
+) -> SimulationResult {
+    let mut phases = PhaseTracker::new(&config.schedule);
+    let mut run = AttackRun::new(config, adversary, rng);
+
+    for block_number in 1..=config.total_blocks {
+        let start = run.begin_block(block_number, rng);
+
+        let behaviors = phases.advance(block_number, start.open_blobs, !start.proposer_honest);
+        let withholding = behaviors.contains(&Behavior::Withhold);
+        // Withholding malicious nodes back the blob, otherwise they vote No
+        let attack = Attack {
+            withhold: withholding,
+            censor: behaviors.contains(&Behavior::Censor) && !start.proposer_honest,
+            malicious_vote: Some(withholding),
+        };
+        let adaptive = behaviors.contains(&Behavior::Adaptive);
+        let block = run.finish_block(block_number, rng, attack, adaptive);
+
+        debug!(
+            block = block.number,
+            proposer = block.proposer,
+            behaviors = ?behaviors,
+            "block produced"
+        );
+        on_block(block);
+    }
+
+    let phases = phases.finish();
+    for phase in &phases {
+        info!(
+            phase = %phase.name,
+            start = phase.start_block,
+            end = phase.end_block,
+            "attack phase"
+        );
+    }
+    run.into_result(Scenario::Scheduled, phases)
+}
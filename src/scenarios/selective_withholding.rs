@@ -0,0 +1,210 @@
+use super::blob_queue::BlobQueue;
+use super::schedule::attack_phases;
+use super::stake::node_weights;
+use super::vote_gate::VoteGate;
+use crate::models::{
+    Behavior, Blob, Block, SelectiveMetrics, Settings, SimulationResult, SplitVote, Targeting,
+    VoteMessage, VoteRecord,
+};
+use crate::simulation::Scenario;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use tracing::debug;
+
+// Honest nodes in the order the adversary serves them an attacked blob
+fn serving_order(
+    targeting: Targeting,
+    honest: &[usize],
+    weights: &[f64],
+    previous_voters: &[usize],
+    rng: &mut StdRng,
+) -> Vec<usize> {
+    match targeting {
+        Targeting::Random => {
+            let mut order = honest.to_vec();
+            order.shuffle(rng);
+            order
+        }
+        Targeting::LowestWeight => {
+            let mut order = honest.to_vec();
+            order.sort_by(|a, b| weights[*a].total_cmp(&weights[*b]).then(a.cmp(b)));
+            order
+        }
+        Targeting::PreviousVoters => {
+            // Earlier voters first, then everyone else by node id
+            let mut order: Vec<usize> = Vec::with_capacity(honest.len());
+            let mut seen = HashSet::new();
+            for &node in previous_voters {
+                if seen.insert(node) {
+                    order.push(node);
+                }
+            }
+            order.extend(honest.iter().filter(|node| !seen.contains(node)));
+            order
+        }
+    }
+}
+
+pub(crate) fn run(
+    config: &Settings,
+    rng: &mut StdRng,
+    on_block: &mut dyn FnMut(&Block),
+) -> SimulationResult {
+    let honest_nodes: HashSet<usize> = (0..(config.total_nodes - config.malicious_nodes)).collect();
+    let honest: Vec<usize> = (0..(config.total_nodes - config.malicious_nodes)).collect();
+    let all_nodes: Vec<usize> = (0..config.total_nodes).collect();
+    let weights = node_weights(config, rng);
+
+    let mut blobs: HashMap<usize, Blob> = HashMap::new();
+    let mut blob_queue = BlobQueue::new(config);
+    let mut gate = VoteGate::new(config);
+    let mut blocks: Vec<Block> = Vec::new();
+    let mut vote_records: Vec<VoteRecord> = Vec::new();
+
+    // Same attack window as data withholding: the last k_f blocks
+    let attack_start_block = config.total_blocks.saturating_sub(config.k_f);
+    let served_count =
+        ((config.selective.serve_fraction.clamp(0.0, 1.0) * honest.len() as f64).round()) as usize;
+
+    // Honest nodes each attacked blob is served to, fixed when the attack reaches it
+    let mut served: HashMap<usize, HashSet<usize>> = HashMap::new();
+    let mut honest_voters: HashMap<usize, Vec<usize>> = HashMap::new();
+    let mut splits: BTreeMap<usize, SplitVote> = BTreeMap::new();
+
+    for block_number in 1..=config.total_blocks {
+        let block_proposer = all_nodes.choose(rng).unwrap();
+        let proposer_honest = honest_nodes.contains(block_proposer);
+
+        blob_queue.arrive(block_number, rng, &mut blobs);
+        let included_blobs = blob_queue.include(block_number, &mut blobs);
+
+        let selected_nodes: Vec<usize> = all_nodes
+            .choose_multiple(rng, config.nodes_per_block)
+            .cloned()
+            .collect();
+        gate.open_round(block_number, &selected_nodes);
+
+        let attack_active = block_number >= attack_start_block;
+        let mut block_votes: HashMap<usize, Vec<Option<bool>>> = HashMap::new();
+
+        for &blob_id in blob_queue.unconfirmed() {
+            let split = splits.entry(blob_id).or_insert_with(|| SplitVote {
+                blob_id,
+                ..Default::default()
+            });
+            if attack_active && !served.contains_key(&blob_id) {
+                let voters = honest_voters.get(&blob_id).map_or(&[][..], Vec::as_slice);
+                let order =
+                    serving_order(config.selective.targeting, &honest, &weights, voters, rng);
+                served.insert(blob_id, order.into_iter().take(served_count).collect());
+                split.attacked = true;
+            }
+
+            let blob = blobs.get_mut(&blob_id).unwrap();
+            let mut votes = Vec::with_capacity(selected_nodes.len());
+            for &node in &selected_nodes {
+                let honest = honest_nodes.contains(&node);
+                // Honest nodes vote Yes only if the blob's data was served to them, and
+                // malicious nodes back blobs they serve selectively
+                let vote = if honest {
+                    served
+                        .get(&blob_id)
+                        .is_none_or(|served| served.contains(&node))
+                } else {
+                    attack_active
+                };
+                let message = VoteMessage::sign(node, blob_id, block_number, vote);
+                if gate.admit(&message, blob, block_number).is_err() {
+                    votes.push(None);
+                    continue;
+                }
+                if honest {
+                    honest_voters.entry(blob_id).or_default().push(node);
+                    if vote {
+                        blob.votes_honest += 1;
+                        split.honest_yes += 1;
+                    } else {
+                        split.honest_no += 1;
+                    }
+                } else if !vote {
+                    blob.votes_malicious += 1;
+                }
+                if vote {
+                    split.yes_weight += weights[node];
+                } else {
+                    split.no_weight += weights[node];
+                }
+                vote_records.push(VoteRecord::included(
+                    node,
+                    blob_id,
+                    vote,
+                    honest,
+                    block_number,
+                    proposer_honest,
+                ));
+                votes.push(Some(vote));
+            }
+            block_votes.insert(blob_id, votes);
+        }
+
+        let block = Block {
+            number: block_number,
+            proposer: *block_proposer,
+            blobs: included_blobs,
+            selected_nodes,
+            votes: block_votes,
+            buffered_votes: HashMap::new(),
+            header: None,
+        };
+
+        gate.attack(&block, &blobs, rng);
+        for blob_id in blob_queue.confirm(block.number, &mut blobs) {
+            if let Some(split) = splits.get_mut(&blob_id) {
+                split.confirmed_at = Some(block.number);
+            }
+        }
+
+        debug!(
+            block = block.number,
+            proposer = block.proposer,
+            "block produced"
+        );
+        on_block(&block);
+        blocks.push(block);
+    }
+
+    let selective = SelectiveMetrics {
+        serve_fraction: config.selective.serve_fraction,
+        targeting: config.selective.targeting.name().to_string(),
+        votes: splits.into_values().collect(),
+    };
+
+    SimulationResult {
+        scenario: Scenario::SelectiveWithholding,
+        settings: config.clone(),
+        honest_nodes,
+        blocks,
+        blobs,
+        votes: vote_records,
+        censorship: None,
+        das: None,
+        selective: Some(selective),
+        adversary: None,
+        fork: None,
+        equivocation: None,
+        congestion: None,
+        spam: None,
+        fees: None,
+        // Selective serving withholds the blob from the unserved honest nodes
+        phases: attack_phases(
+            config.total_blocks,
+            attack_start_block,
+            config.total_blocks,
+            "selective",
+            vec![Behavior::Withhold],
+        ),
+        recovery: None,
+        vote_validation: Some(gate.finish()),
+    }
+}
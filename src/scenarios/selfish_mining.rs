@@ -0,0 +1,307 @@
+use super::blob_queue::BlobQueue;
+use super::chain::BlockTree;
+use super::schedule::attack_phases;
+use super::vote_gate::VoteGate;
+use crate::models::{
+    Behavior, Blob, Block, ForkMetrics, Settings, SimulationResult, VoteMessage, VoteRecord,
+};
+use crate::simulation::Scenario;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use tracing::{debug, info};
+
+// The adversary produces a `malicious_power_block` percent share of blocks on a private
+// branch that leaves honest votes out. After each honest block it publishes the branch
+// when its lead is down to one block or a tie, racing a tie with its next block, and
+// drops it once it falls behind.
+pub(crate) fn run(
+    config: &Settings,
+    rng: &mut StdRng,
+    on_block: &mut dyn FnMut(&Block),
+) -> SimulationResult {
+    let honest_nodes: HashSet<usize> = (0..(config.total_nodes - config.malicious_nodes)).collect();
+    let honest_list: Vec<usize> = (0..(config.total_nodes - config.malicious_nodes)).collect();
+    let malicious_list: Vec<usize> =
+        ((config.total_nodes - config.malicious_nodes)..config.total_nodes).collect();
+    let all_nodes: Vec<usize> = (0..config.total_nodes).collect();
+
+    let power = config.malicious_power_block as f64 / 100.0;
+    let capacity = config.max_blobs_per_block.unwrap_or(usize::MAX);
+    let depth = config.confirmation_depth.max(1);
+
+    let mut blobs: HashMap<usize, Blob> = HashMap::new();
+    // Inclusion is tracked per branch, so the queue is only used for arrivals
+    let mut blob_queue = BlobQueue::new(config);
+    let mut gate = VoteGate::new(config);
+    let mut tree = BlockTree::new(config.fork_choice);
+    let mut private_tip: Option<usize> = None;
+    // Confirmed blobs and verdicts as seen from the canonical head
+    let mut confirmed: HashMap<usize, bool> = HashMap::new();
+    let mut flipped: BTreeSet<usize> = BTreeSet::new();
+    let mut metrics = ForkMetrics {
+        fork_choice: config.fork_choice.name().to_string(),
+        malicious_power: power,
+        ..Default::default()
+    };
+
+    for slot in 1..=config.total_blocks {
+        blob_queue.arrive(slot, rng, &mut blobs);
+        let arrived = blobs.len();
+
+        // Block producers have the adversary's power share, not its node share
+        let malicious =
+            !malicious_list.is_empty() && (honest_list.is_empty() || rng.gen_bool(power));
+        let proposer = if malicious {
+            *malicious_list.choose(rng).unwrap()
+        } else {
+            *honest_list.choose(rng).unwrap()
+        };
+        let selected_nodes: Vec<usize> = all_nodes
+            .choose_multiple(rng, config.nodes_per_block)
+            .cloned()
+            .collect();
+        gate.open_round(slot, &selected_nodes);
+
+        let old_head = tree.head();
+        let parent = if malicious {
+            private_tip.or(old_head)
+        } else {
+            old_head
+        };
+        let blobs_through = parent.map_or(0, |parent| tree.get(parent).blobs_through);
+        let included: Vec<usize> = (blobs_through..arrived).take(capacity).collect();
+
+        // Blobs open for votes on this branch: included in the last `depth` blocks
+        let mut open: BTreeSet<usize> = included.iter().copied().collect();
+        for ancestor in tree.ancestors(parent).take(depth - 1) {
+            open.extend(&tree.get(ancestor).block.blobs);
+        }
+
+        let mut block_votes: HashMap<usize, Vec<Option<bool>>> = HashMap::new();
+        for &blob_id in &open {
+            let votes: Vec<Option<bool>> = selected_nodes
+                .iter()
+                .map(|&node| {
+                    let decision = match (honest_nodes.contains(&node), malicious) {
+                        // The adversary's blocks leave honest votes out
+                        (true, true) => {
+                            metrics.censored_votes += 1;
+                            return None;
+                        }
+                        (true, false) => true,
+                        (false, _) => false,
+                    };
+                    let message = VoteMessage::sign(node, blob_id, slot, decision);
+                    gate.admit(&message, &blobs[&blob_id], slot)
+                        .ok()
+                        .map(|()| decision)
+                })
+                .collect();
+            block_votes.insert(blob_id, votes);
+        }
+
+        // Blocks are numbered by slot, so every branch's blocks have distinct numbers
+        let block = Block {
+            number: slot,
+            proposer,
+            blobs: included.clone(),
+            selected_nodes,
+            votes: block_votes,
+            buffered_votes: HashMap::new(),
+            header: None,
+        };
+        gate.attack(&block, &blobs, rng);
+        on_block(&block);
+        let index = tree.push(parent, block, blobs_through + included.len(), malicious);
+        metrics.produced_blocks += 1;
+
+        if malicious {
+            metrics.adversary_blocks += 1;
+            if private_tip.is_some_and(|tip| tree.get(tip).published) {
+                // Racing a tied public branch: the new block settles it right away
+                tree.publish(index);
+                private_tip = None;
+            } else {
+                private_tip = Some(index);
+            }
+        } else {
+            tree.publish(index);
+            if let Some(tip) = private_tip {
+                // Release the branch while it can still match or beat the public one
+                let lead = tree.get(tip).height as isize - tree.get(index).height as isize;
+                match lead {
+                    ..=-1 => private_tip = None,
+                    0 => tree.publish(tip),
+                    1 => {
+                        tree.publish(tip);
+                        private_tip = None;
+                    }
+                    _ => {}
+                }
+            }
+        }
+        if slot == config.total_blocks {
+            if let Some(tip) = private_tip.take() {
+                tree.publish(tip);
+            }
+        }
+
+        // Follow the canonical head, undoing confirmations a reorg removes
+        let head = tree.head();
+        match (old_head, head) {
+            (Some(old), Some(new)) if old != new => {
+                let ancestor = tree.common_ancestor(old, new);
+                if ancestor == Some(old) {
+                    let added: Vec<usize> = tree
+                        .ancestors(Some(new))
+                        .take_while(|&block| block != old)
+                        .collect();
+                    for block in added.into_iter().rev() {
+                        confirmed.extend(tree.confirmations(block, depth));
+                    }
+                } else {
+                    let ancestor_height = ancestor.map_or(0, |block| tree.get(block).height);
+                    let reorg_depth = tree.get(old).height - ancestor_height;
+                    metrics.reorg_depths.push(reorg_depth);
+                    let view = tree.confirmed_view(head, depth);
+                    for (blob_id, accepted) in &confirmed {
+                        if view.get(blob_id) != Some(accepted) {
+                            flipped.insert(*blob_id);
+                        }
+                    }
+                    confirmed = view;
+                    debug!(block = slot, depth = reorg_depth, "reorg");
+                }
+            }
+            (None, Some(new)) => confirmed = tree.confirmed_view(Some(new), depth),
+            _ => {}
+        }
+    }
+
+    // The final canonical chain becomes the run's blocks, everything else is orphaned
+    let canonical = tree.path(tree.head());
+    let on_chain: HashSet<usize> = canonical.iter().copied().collect();
+    for index in (0..tree.len()).filter(|index| !on_chain.contains(index)) {
+        metrics.orphaned_blocks += 1;
+        let block = &tree.get(index).block;
+        for (node, vote) in block
+            .votes
+            .values()
+            .flat_map(|votes| block.selected_nodes.iter().zip(votes))
+        {
+            if vote.is_some() {
+                if honest_nodes.contains(node) {
+                    metrics.orphaned_votes_honest += 1;
+                } else {
+                    metrics.orphaned_votes_malicious += 1;
+                }
+            }
+        }
+    }
+    metrics.canonical_adversary_blocks = canonical
+        .iter()
+        .filter(|&&index| tree.get(index).malicious)
+        .count();
+    metrics.flipped_blobs = flipped.into_iter().collect();
+
+    let blocks: Vec<Block> = canonical
+        .iter()
+        .map(|&index| tree.get(index).block.clone())
+        .collect();
+    let mut vote_records: Vec<VoteRecord> = Vec::new();
+    for (position, block) in blocks.iter().enumerate() {
+        let proposer_honest = honest_nodes.contains(&block.proposer);
+        let confirming_block = blocks.get(position + depth - 1);
+        for &blob_id in &block.blobs {
+            let blob = blobs.get_mut(&blob_id).unwrap();
+            blob.included_at = Some(block.number);
+            if let Some(confirming_block) = confirming_block {
+                blob.is_confirmed = true;
+                blob.confirmed_at = Some(confirming_block.number);
+            }
+        }
+        let mut blob_ids: Vec<&usize> = block.votes.keys().collect();
+        blob_ids.sort_unstable();
+        for &blob_id in blob_ids {
+            let blob = blobs.get_mut(&blob_id).unwrap();
+            for (&node, vote) in block.selected_nodes.iter().zip(&block.votes[&blob_id]) {
+                let voter_honest = honest_nodes.contains(&node);
+                match vote {
+                    Some(decision) => {
+                        if voter_honest && *decision {
+                            blob.votes_honest += 1;
+                        } else if !voter_honest && !*decision {
+                            blob.votes_malicious += 1;
+                        }
+                        vote_records.push(VoteRecord::included(
+                            node,
+                            blob_id,
+                            *decision,
+                            voter_honest,
+                            block.number,
+                            proposer_honest,
+                        ));
+                    }
+                    // Honest votes missing from an adversary block were censored
+                    None if voter_honest && !proposer_honest => {
+                        vote_records.push(VoteRecord::censored(
+                            node,
+                            blob_id,
+                            true,
+                            voter_honest,
+                            block.number,
+                            proposer_honest,
+                        ))
+                    }
+                    // Any other missing vote was cast, honest Yes or malicious No, and
+                    // rejected by the vote gate
+                    None => vote_records.push(VoteRecord::censored(
+                        node,
+                        blob_id,
+                        voter_honest,
+                        voter_honest,
+                        block.number,
+                        proposer_honest,
+                    )),
+                }
+            }
+        }
+    }
+
+    info!(
+        canonical_blocks = blocks.len(),
+        orphaned_blocks = metrics.orphaned_blocks,
+        reorgs = metrics.reorg_depths.len(),
+        flipped_blobs = metrics.flipped_blobs.len(),
+        "fork totals"
+    );
+
+    SimulationResult {
+        scenario: Scenario::SelfishMining,
+        settings: config.clone(),
+        honest_nodes,
+        blocks,
+        blobs,
+        votes: vote_records,
+        censorship: None,
+        das: None,
+        selective: None,
+        adversary: None,
+        fork: Some(metrics),
+        equivocation: None,
+        congestion: None,
+        spam: None,
+        fees: None,
+        phases: attack_phases(
+            config.total_blocks,
+            1,
+            config.total_blocks,
+            "selfish",
+            vec![Behavior::Censor],
+        ),
+        recovery: None,
+        vote_validation: Some(gate.finish()),
+    }
+}
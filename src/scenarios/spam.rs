@@ -0,0 +1,223 @@
+use super::blob_queue::BlobQueue;
+use super::vote_gate::VoteGate;
+use crate::models::{
+    Blob, Block, Settings, SimulationResult, SpamMetrics, VoteMessage, VoteRecord,
+};
+use crate::simulation::Scenario;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use rand_distr::{Distribution, Poisson};
+use std::collections::{HashMap, HashSet, VecDeque};
+use tracing::{debug, info};
+
+// Honest blobs arrive as usual while malicious nodes submit `spam.rate` junk blobs per
+// block behind them, each paying `spam.fee` from the adversary's budget and counting
+// against the submitter's rate limit. Junk blobs share the mempool and
+// `max_blobs_per_block` with honest ones, so they delay honest inclusion, and every
+// committee votes on them while they are open. Honest nodes vote Yes unless the
+// blob's data is unavailable; malicious nodes vote Yes on junk and No otherwise.
+pub(crate) fn run(
+    config: &Settings,
+    rng: &mut StdRng,
+    on_block: &mut dyn FnMut(&Block),
+) -> SimulationResult {
+    let honest_nodes: HashSet<usize> = (0..(config.total_nodes - config.malicious_nodes)).collect();
+    let malicious_nodes: Vec<usize> =
+        ((config.total_nodes - config.malicious_nodes)..config.total_nodes).collect();
+    let all_nodes: Vec<usize> = (0..config.total_nodes).collect();
+    let settings = &config.spam;
+
+    let mut metrics = SpamMetrics::default();
+    let mut budget = settings.budget.unwrap_or(f64::INFINITY);
+    // Blocks of each node's recent submissions, for the rate limit
+    let mut submissions: HashMap<usize, VecDeque<usize>> = HashMap::new();
+
+    let mut blobs: HashMap<usize, Blob> = HashMap::new();
+    let mut blob_queue = BlobQueue::new(config);
+    let mut gate = VoteGate::new(config);
+    let mut blocks: Vec<Block> = Vec::new();
+    let mut vote_records: Vec<VoteRecord> = Vec::new();
+
+    for block in 1..=config.total_blocks {
+        let block_proposer = *all_nodes.choose(rng).unwrap();
+        let proposer_honest = honest_nodes.contains(&block_proposer);
+
+        blob_queue.arrive(block, rng, &mut blobs);
+        let attempts = if settings.rate > 0.0 && !malicious_nodes.is_empty() {
+            Poisson::new(settings.rate).unwrap().sample(rng) as usize
+        } else {
+            0
+        };
+        for _ in 0..attempts {
+            let submitter = *malicious_nodes.choose(rng).unwrap();
+            let recent = submissions.entry(submitter).or_default();
+            if let Some(limit) = &settings.rate_limit {
+                while recent.front().is_some_and(|&at| at + limit.window <= block) {
+                    recent.pop_front();
+                }
+                if recent.len() >= limit.blobs {
+                    metrics.refused_rate_limit += 1;
+                    continue;
+                }
+            }
+            if settings.fee > budget {
+                metrics.refused_budget += 1;
+                continue;
+            }
+            if settings.rate_limit.is_some() {
+                recent.push_back(block);
+            }
+            budget -= settings.fee;
+            metrics.fees_paid += settings.fee;
+            metrics.submitted += 1;
+
+            let blob_id = blob_queue.submit(block, settings.size, &mut blobs);
+            let blob = blobs.get_mut(&blob_id).unwrap();
+            blob.proposer_status = "malicious".to_string();
+            if rng.gen_bool(settings.unavailable) {
+                blob.recoverable = Some(false);
+                metrics.unavailable += 1;
+            }
+        }
+        let included_blobs = blob_queue.include(block, &mut blobs);
+
+        let selected_nodes: Vec<usize> = all_nodes
+            .choose_multiple(rng, config.nodes_per_block)
+            .cloned()
+            .collect();
+        gate.open_round(block, &selected_nodes);
+
+        let mut block_votes: HashMap<usize, Vec<Option<bool>>> = HashMap::new();
+        for &blob_id in blob_queue.unconfirmed() {
+            let blob = blobs.get_mut(&blob_id).unwrap();
+            let junk = blob.proposer_status == "malicious";
+            let available = blob.recoverable != Some(false);
+            let mut votes = Vec::with_capacity(selected_nodes.len());
+            for &node in &selected_nodes {
+                let honest = honest_nodes.contains(&node);
+                let decision = if honest { available } else { junk };
+                let message = VoteMessage::sign(node, blob_id, block, decision);
+                if gate.admit(&message, blob, block).is_err() {
+                    votes.push(None);
+                    continue;
+                }
+                if honest {
+                    if decision {
+                        blob.votes_honest += 1;
+                    }
+                } else if !decision {
+                    blob.votes_malicious += 1;
+                }
+                vote_records.push(VoteRecord::included(
+                    node,
+                    blob_id,
+                    decision,
+                    honest,
+                    block,
+                    proposer_honest,
+                ));
+                votes.push(Some(decision));
+            }
+            block_votes.insert(blob_id, votes);
+        }
+
+        let block = Block {
+            number: block,
+            proposer: block_proposer,
+            blobs: included_blobs,
+            selected_nodes,
+            votes: block_votes,
+            buffered_votes: HashMap::new(),
+            header: None,
+        };
+
+        gate.attack(&block, &blobs, rng);
+        blob_queue.confirm(block.number, &mut blobs);
+        debug!(
+            block = block.number,
+            attempts,
+            submitted = metrics.submitted,
+            "block produced"
+        );
+        on_block(&block);
+        blocks.push(block);
+    }
+
+    info!(
+        submitted = metrics.submitted,
+        refused_budget = metrics.refused_budget,
+        refused_rate_limit = metrics.refused_rate_limit,
+        fees_paid = metrics.fees_paid,
+        "spam totals"
+    );
+
+    SimulationResult {
+        scenario: Scenario::Spam,
+        settings: config.clone(),
+        honest_nodes,
+        blocks,
+        blobs,
+        votes: vote_records,
+        censorship: None,
+        das: None,
+        selective: None,
+        adversary: None,
+        fork: None,
+        equivocation: None,
+        congestion: None,
+        spam: Some(metrics),
+        fees: None,
+        phases: Vec::new(),
+        recovery: None,
+        vote_validation: Some(gate.finish()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::RateLimit;
+    use rand::SeedableRng;
+
+    fn spam(settings: Settings) -> SpamMetrics {
+        let mut rng = StdRng::seed_from_u64(7);
+        run(&settings, &mut rng, &mut |_| {}).spam.unwrap()
+    }
+
+    #[test]
+    fn rate_limit_refuses_submissions_over_the_window() {
+        let mut settings = Settings::for_tests();
+        settings.spam.rate = 3.0;
+        // One blob per node for the whole run
+        settings.spam.rate_limit = Some(RateLimit {
+            blobs: 1,
+            window: settings.total_blocks,
+        });
+        let metrics = spam(settings.clone());
+        assert!(metrics.submitted <= settings.malicious_nodes);
+        assert!(metrics.refused_rate_limit > 0);
+        assert_eq!(metrics.refused_budget, 0);
+
+        // A one-block window lets every node submit once per block
+        settings.spam.rate_limit = Some(RateLimit {
+            blobs: 1,
+            window: 1,
+        });
+        let metrics = spam(settings.clone());
+        assert!(metrics.submitted > settings.malicious_nodes);
+    }
+
+    #[test]
+    fn budget_refuses_submissions_it_cannot_pay_for() {
+        let mut settings = Settings::for_tests();
+        settings.spam.rate = 3.0;
+        settings.spam.fee = 1.0;
+        settings.spam.budget = Some(3.5);
+        let metrics = spam(settings);
+        assert_eq!(metrics.submitted, 3);
+        assert_eq!(metrics.fees_paid, 3.0);
+        assert!(metrics.refused_budget > 0);
+        assert_eq!(metrics.refused_rate_limit, 0);
+    }
+}
@@ -0,0 +1,20 @@
+use crate::models::{Settings, StakeDistribution};
+use rand::rngs::StdRng;
+use rand::Rng;
+use rand_distr::{Distribution, Pareto};
+
+// Stake of every node, indexed by node id. Equal stake draws nothing from the RNG.
+pub(crate) fn node_weights(config: &Settings, rng: &mut StdRng) -> Vec<f64> {
+    match config.stake {
+        StakeDistribution::Equal => vec![1.0; config.total_nodes],
+        StakeDistribution::Uniform { min, max } => (0..config.total_nodes)
+            .map(|_| rng.gen_range(min..max))
+            .collect(),
+        StakeDistribution::Pareto { scale, shape } => {
+            let pareto = Pareto::new(scale, shape).unwrap();
+            (0..config.total_nodes)
+                .map(|_| pareto.sample(rng))
+                .collect()
+        }
+    }
+}
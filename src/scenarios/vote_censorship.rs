@@ -1,100 +1,97 @@
-use rand::seq::SliceRandom;
-use sim::{
-    models::{Blob, Block, Settings},
-    utils::csv_writer::create_results_csv,
-    utils::{
-        create_voting_summary_per_blob, create_voting_summary_per_block, write_table_buffered,
-    },
+use super::blob_queue::BlobQueue;
+use super::pending::PendingVotes;
+use super::schedule::attack_phases;
+use super::vote_gate::VoteGate;
+use crate::models::{
+    Behavior, Blob, Block, CensorshipMetrics, Settings, SimulationResult, VoteMessage, VoteRecord,
 };
+use crate::simulation::Scenario;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
 use std::collections::{HashMap, HashSet};
-use std::fs::File;
-use std::io::BufWriter;
-
-#[allow(dead_code)]
-pub fn run() {
-    let config = Settings::load("config");
-    let mut rng = rand::thread_rng();
-    let mut total_honest_votes = 0;
-    let mut total_malicious_votes = 0;
+use tracing::{debug, info};
 
-    let mut buffer_votes: HashMap<usize, Vec<Option<bool>>> = HashMap::new();
+pub(crate) fn run(
+    config: &Settings,
+    rng: &mut StdRng,
+    on_block: &mut dyn FnMut(&Block),
+) -> SimulationResult {
+    let mut metrics = CensorshipMetrics::default();
+    let mut pending_votes = PendingVotes::new();
+    let censorship_end_block = config.censorship_end_block.unwrap_or(usize::MAX);
 
     let honest_nodes: HashSet<usize> = (0..(config.total_nodes - config.malicious_nodes)).collect();
-    let _malicious_nodes: HashSet<usize> =
-        ((config.total_nodes - config.malicious_nodes)..config.total_nodes).collect();
-    let all_nodes: HashSet<usize> = (0..config.total_nodes).collect();
+    let all_nodes: Vec<usize> = (0..config.total_nodes).collect();
 
     let mut blobs: HashMap<usize, Blob> = HashMap::new();
-    let mut unconfirmed_blobs: HashSet<usize> = HashSet::new();
-    let mut next_blob_id = 0;
+    let mut blob_queue = BlobQueue::new(config);
+    let mut gate = VoteGate::new(config);
 
     let mut blocks: Vec<Block> = Vec::new();
+    let mut vote_records: Vec<VoteRecord> = Vec::new();
 
     for block in 1..=config.total_blocks {
-        let block_proposer = *all_nodes
-            .iter()
-            .collect::<Vec<_>>()
-            .choose(&mut rng)
-            .unwrap();
-
-        // Create new blob for this block
-        let new_blob = sim::models::Blob::new(next_blob_id);
-        blobs.insert(next_blob_id, new_blob);
-        unconfirmed_blobs.insert(next_blob_id);
-        next_blob_id += 1;
+        let block_proposer = all_nodes.choose(rng).unwrap();
+        let proposer_honest = honest_nodes.contains(block_proposer);
+        let censoring = !proposer_honest && block < censorship_end_block;
+
+        blob_queue.arrive(block, rng, &mut blobs);
+        let included_blobs = blob_queue.include(block, &mut blobs);
 
         // Select nodes for this block
         let selected_nodes: Vec<usize> = all_nodes
-            .iter()
-            .cloned()
-            .collect::<Vec<_>>()
-            .choose_multiple(&mut rng, config.nodes_per_block)
+            .choose_multiple(rng, config.nodes_per_block)
             .cloned()
             .collect();
+        gate.open_round(block, &selected_nodes);
 
         // Create votes for each unconfirmed blob
         let mut block_votes: HashMap<usize, Vec<Option<bool>>> = HashMap::new();
 
-        for &blob_id in &unconfirmed_blobs {
-            let mut votes = Vec::new();
-            // delete the confirmed blobs from the buffer
-            let to_remove: Vec<_> = buffer_votes
-                .keys()
-                .filter(|&blob_id| blobs.get(blob_id).unwrap().is_confirmed)
-                .copied()
-                .collect();
-
-            for blob_id in to_remove {
-                buffer_votes.remove(&blob_id);
-            }
+        for &blob_id in blob_queue.unconfirmed() {
+            let blob = blobs.get_mut(&blob_id).unwrap();
+            let mut votes = Vec::with_capacity(selected_nodes.len());
+
             for &node in &selected_nodes {
-                // Only record votes if the proposer is honest
-                let vote = if honest_nodes.contains(&node) && block < config.k_f {
-                    if !honest_nodes.contains(&block_proposer) {
-                        // If proposer is malicious, buffer the vote
-                        buffer_votes
-                            .entry(blob_id)
-                            .or_insert(Vec::new())
-                            .push(Some(true));
-                        total_honest_votes += 1;
-                        None
-                    } else {
-                        // If proposer is honest, include current vote and check buffered votes
-                        for (blob_id, buffered_votes) in &buffer_votes {
-                            for &vote in buffered_votes {
-                                if vote.is_some() {
-                                    blobs.get_mut(blob_id).unwrap().votes_honest += 1;
-                                }
-                            }
-                        }
-                        blobs.get_mut(&blob_id).unwrap().votes_honest += 1;
-                        total_honest_votes += 1;
-                        Some(true)
+                let honest = honest_nodes.contains(&node);
+                let message = VoteMessage::sign(node, blob_id, block, honest);
+                let vote = if !honest {
+                    if gate.admit(&message, blob, block).is_err() {
+                        votes.push(None);
+                        continue;
                     }
-                } else {
-                    total_malicious_votes += 1;
-                    blobs.get_mut(&blob_id).unwrap().votes_malicious += 1;
+                    blob.votes_malicious += 1;
+                    vote_records.push(VoteRecord::included(
+                        node,
+                        blob_id,
+                        false,
+                        false,
+                        block,
+                        proposer_honest,
+                    ));
                     Some(false)
+                } else if censoring {
+                    pending_votes.push(
+                        VoteRecord::censored(node, blob_id, true, true, block, proposer_honest),
+                        &mut vote_records,
+                    );
+                    metrics.censored_votes += 1;
+                    None
+                } else {
+                    if gate.admit(&message, blob, block).is_err() {
+                        votes.push(None);
+                        continue;
+                    }
+                    blob.votes_honest += 1;
+                    vote_records.push(VoteRecord::included(
+                        node,
+                        blob_id,
+                        true,
+                        true,
+                        block,
+                        proposer_honest,
+                    ));
+                    Some(true)
                 };
                 votes.push(vote);
             }
@@ -102,55 +99,75 @@ pub fn run() {
             block_votes.insert(blob_id, votes);
         }
 
-        let block = if honest_nodes.contains(&block_proposer) {
-            sim::models::Block {
-                number: block,
-                proposer: *block_proposer,
-                selected_nodes,
-                votes: block_votes,
-                buffered_votes: buffer_votes.clone(),
-            }
+        let buffered_votes = if proposer_honest {
+            pending_votes.drain_into(
+                block,
+                &mut gate,
+                &mut blobs,
+                &mut vote_records,
+                &mut metrics,
+            )
         } else {
-            sim::models::Block {
-                number: block,
-                proposer: *block_proposer,
-                selected_nodes,
-                votes: block_votes,
-                buffered_votes: HashMap::new(),
-            }
+            HashMap::new()
         };
-        if honest_nodes.contains(&block_proposer) {
-            buffer_votes.clear();
-        }
 
-        if block.number >= config.confirmation_depth {
-            let confirmed_blob_id = block.number - config.confirmation_depth;
-            if let Some(blob) = blobs.get_mut(&confirmed_blob_id) {
-                blob.is_confirmed = true;
-                unconfirmed_blobs.remove(&confirmed_blob_id);
-            }
-        }
-        println!("Block Number: {}", block.number);
+        let block = Block {
+            number: block,
+            proposer: *block_proposer,
+            blobs: included_blobs,
+            selected_nodes,
+            votes: block_votes,
+            buffered_votes,
+            header: None,
+        };
+
+        gate.attack(&block, &blobs, rng);
+        let confirmed = blob_queue.confirm(block.number, &mut blobs);
+        metrics.lost_votes += pending_votes.drop_confirmed(&confirmed);
+        debug!(
+            block = block.number,
+            proposer = block.proposer,
+            pending = pending_votes.len(),
+            "block produced"
+        );
+        on_block(&block);
         blocks.push(block);
     }
-
-    let file = File::create("simulation_results_per_block_vc.txt").expect("Unable to create file");
-    let mut writer = BufWriter::new(file);
-    let table = create_voting_summary_per_block(&blocks, &honest_nodes);
-    write_table_buffered(&table, &mut writer).expect("Unable to write table");
-
-    let file = File::create("simulation_results_per_blob_vc.txt").expect("Unable to create file");
-    let mut writer = BufWriter::new(file);
-    let table = create_voting_summary_per_blob(&blobs);
-    write_table_buffered(&table, &mut writer).expect("Unable to write table");
-
-    let file = File::create("simulation_results_vc.csv").expect("Unable to create file");
-    let mut writer = BufWriter::new(file);
-    create_results_csv(&blocks, &honest_nodes, &mut writer).expect("Unable to write CSV");
-
-    println!("Total honest votes: {}", total_honest_votes);
-    println!("Total malicious votes: {}", total_malicious_votes);
-    println!("Buffer votes: {}", buffer_votes.len());
-
-    println!("Simulation complete. Results written to 'simulation_results_vc.txt' and 'simulation_results_per_block_vc.txt' and 'simulation_results_per_blob_vc.txt'.");
+    metrics.pending_votes = pending_votes.len();
+
+    info!(
+        censored_votes = metrics.censored_votes,
+        included_votes = metrics.included_votes,
+        lost_votes = metrics.lost_votes,
+        pending_votes = metrics.pending_votes,
+        mean_inclusion_delay = metrics.mean_inclusion_delay(),
+        "censorship totals"
+    );
+
+    SimulationResult {
+        scenario: Scenario::VoteCensorship,
+        settings: config.clone(),
+        honest_nodes,
+        blocks,
+        blobs,
+        votes: vote_records,
+        censorship: Some(metrics),
+        das: None,
+        selective: None,
+        adversary: None,
+        fork: None,
+        equivocation: None,
+        congestion: None,
+        spam: None,
+        fees: None,
+        phases: attack_phases(
+            config.total_blocks,
+            1,
+            censorship_end_block.saturating_sub(1),
+            "censor",
+            vec![Behavior::Censor],
+        ),
+        recovery: None,
+        vote_validation: Some(gate.finish()),
+    }
 }
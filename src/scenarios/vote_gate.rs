@@ -0,0 +1,154 @@
+use crate::models::{
+    Blob, Block, InvalidVote, InvalidVoteSettings, Settings, VoteMessage, VoteValidationMetrics,
+};
+use crate::utils::vote_validation::VoteValidator;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::collections::HashMap;
+
+// Every vote passes through here as a signed `VoteMessage` before it reaches a block
+// or a tally. After each block the adversary also sends `invalid_votes.per_block`
+// messages, each built to break one rule, which the validator rejects.
+pub(crate) struct VoteGate {
+    validator: VoteValidator,
+    settings: InvalidVoteSettings,
+    malicious: Vec<usize>,
+    // Messages accepted for the current block, replayed by duplicate attempts
+    accepted: Vec<VoteMessage>,
+    metrics: VoteValidationMetrics,
+}
+
+impl VoteGate {
+    pub(crate) fn new(config: &Settings) -> Self {
+        Self {
+            validator: VoteValidator::new(),
+            settings: config.invalid_votes.clone(),
+            malicious: ((config.total_nodes - config.malicious_nodes)..config.total_nodes)
+                .collect(),
+            accepted: Vec::new(),
+            metrics: VoteValidationMetrics::default(),
+        }
+    }
+
+    // Starts block `block`, whose committee votes from now on
+    pub(crate) fn open_round(&mut self, block: usize, committee: &[usize]) {
+        self.validator.open_round(block, committee);
+        self.accepted.clear();
+    }
+
+    // Validates `message` for inclusion in block `included_at`; only accepted votes
+    // may be put in the block and counted
+    pub(crate) fn admit(
+        &mut self,
+        message: &VoteMessage,
+        blob: &Blob,
+        included_at: usize,
+    ) -> Result<(), InvalidVote> {
+        match self.validator.check(message, Some(blob), included_at) {
+            Ok(()) => {
+                self.metrics.accepted += 1;
+                self.accepted.push(message.clone());
+                Ok(())
+            }
+            Err(reason) => {
+                self.metrics.rejected_included += 1;
+                Err(reason)
+            }
+        }
+    }
+
+    // Whether `message` contradicts a vote already accepted in its round
+    pub(crate) fn conflicts(&self, message: &VoteMessage) -> bool {
+        self.validator.conflicts(message)
+    }
+
+    // The adversary's invalid attempts against `block`, all of which are rejected
+    pub(crate) fn attack(&mut self, block: &Block, blobs: &HashMap<usize, Blob>, rng: &mut StdRng) {
+        if self.settings.kinds.is_empty() {
+            return;
+        }
+        for _ in 0..self.settings.per_block {
+            let kind = *self.settings.kinds.choose(rng).unwrap();
+            let Some(message) = self.forge(kind, block, blobs, rng) else {
+                continue;
+            };
+            *self.metrics.attempted.entry(kind).or_default() += 1;
+            let blob = blobs.get(&message.blob_id);
+            if let Err(reason) = self.validator.check(&message, blob, block.number) {
+                *self.metrics.rejected.entry(reason).or_default() += 1;
+            }
+        }
+    }
+
+    pub(crate) fn finish(self) -> VoteValidationMetrics {
+        self.metrics
+    }
+
+    // Builds a vote message in `block` that breaks only the `kind` rule, or None when
+    // the block offers no way to: no malicious committee member, no open blob, nothing
+    // to replay or nothing confirmed yet
+    fn forge(
+        &self,
+        kind: InvalidVote,
+        block: &Block,
+        blobs: &HashMap<usize, Blob>,
+        rng: &mut StdRng,
+    ) -> Option<VoteMessage> {
+        let members: Vec<usize> = block
+            .selected_nodes
+            .iter()
+            .copied()
+            .filter(|node| self.malicious.contains(node))
+            .collect();
+        let mut open: Vec<usize> = block.votes.keys().copied().collect();
+        open.sort_unstable();
+        let number = block.number;
+
+        match kind {
+            InvalidVote::BadSignature => {
+                let mut message =
+                    VoteMessage::sign(*members.choose(rng)?, *open.choose(rng)?, number, false);
+                // Flipping the decision after signing breaks the signature
+                message.decision = true;
+                Some(message)
+            }
+            InvalidVote::NotInCommittee => {
+                let outsiders: Vec<usize> = self
+                    .malicious
+                    .iter()
+                    .copied()
+                    .filter(|node| !block.selected_nodes.contains(node))
+                    .collect();
+                Some(VoteMessage::sign(
+                    *outsiders.choose(rng)?,
+                    *open.choose(rng)?,
+                    number,
+                    false,
+                ))
+            }
+            InvalidVote::Duplicate => self.accepted.choose(rng).cloned(),
+            // Blob ids are handed out in order, so none at or above the count exists
+            InvalidVote::UnknownBlob => Some(VoteMessage::sign(
+                *members.choose(rng)?,
+                blobs.len() + rng.gen_range(0..=number),
+                number,
+                false,
+            )),
+            InvalidVote::ExpiredBlob => {
+                let mut expired: Vec<usize> = blobs
+                    .values()
+                    .filter(|blob| blob.confirmed_at.is_some_and(|at| at < number))
+                    .map(|blob| blob.id)
+                    .collect();
+                expired.sort_unstable();
+                Some(VoteMessage::sign(
+                    *members.choose(rng)?,
+                    *expired.choose(rng)?,
+                    number,
+                    false,
+                ))
+            }
+        }
+    }
+}
@@ -0,0 +1,586 @@
+use crate::models::{
+    BlobArrival, BlobSize, Block, DasLayout, Settings, SimulationResult, SlashingPenalty,
+    StakeDistribution,
+};
+use crate::scenarios;
+use crate::scenarios::adversary::{self, Adversary};
+use crate::utils::{bandwidth, integrity, recovery};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Scenario {
+    Basic,
+    VoteCensorship,
+    DataWithholding,
+    SmartDataWithholding,
+    SelectiveWithholding,
+    Scheduled,
+    SelfishMining,
+    Equivocation,
+    Congestion,
+    Spam,
+    FeeMarket,
+}
+
+impl Scenario {
+    pub const ALL: [Scenario; 11] = [
+        Scenario::Basic,
+        Scenario::VoteCensorship,
+        Scenario::DataWithholding,
+        Scenario::SmartDataWithholding,
+        Scenario::SelectiveWithholding,
+        Scenario::Scheduled,
+        Scenario::SelfishMining,
+        Scenario::Equivocation,
+        Scenario::Congestion,
+        Scenario::Spam,
+        Scenario::FeeMarket,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Scenario::Basic => "basic",
+            Scenario::VoteCensorship => "vote_censorship",
+            Scenario::DataWithholding => "data_withholding",
+            Scenario::SmartDataWithholding => "smart_data_withholding",
+            Scenario::SelectiveWithholding => "selective_withholding",
+            Scenario::Scheduled => "scheduled",
+            Scenario::SelfishMining => "selfish_mining",
+            Scenario::Equivocation => "equivocation",
+            Scenario::Congestion => "congestion",
+            Scenario::Spam => "spam",
+            Scenario::FeeMarket => "fee_market",
+        }
+    }
+}
+
+impl fmt::Display for Scenario {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Scenario {
+    type Err = SimulationError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Scenario::ALL
+            .into_iter()
+            .find(|scenario| scenario.name() == name)
+            .ok_or_else(|| SimulationError::UnknownScenario(name.to_string()))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SimulationError {
+    MissingSettings,
+    MissingScenario,
+    UnknownScenario(String),
+    InvalidSettings(String),
+}
+
+impl fmt::Display for SimulationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SimulationError::MissingSettings => write!(f, "no settings given"),
+            SimulationError::MissingScenario => write!(f, "no scenario given"),
+            SimulationError::UnknownScenario(name) => write!(f, "unknown scenario: {}", name),
+            SimulationError::InvalidSettings(reason) => write!(f, "invalid settings: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for SimulationError {}
+
+type BlockObserver<'a> = Box<dyn FnMut(&Block) + 'a>;
+
+// Entry point for embedding the simulator, see `Simulation::builder`
+pub struct Simulation;
+
+impl Simulation {
+    pub fn builder<'a>() -> SimulationBuilder<'a> {
+        SimulationBuilder::default()
+    }
+}
+
+// Configures and runs one simulation. Nothing is read from or written to disk;
+// the caller decides what to do with the returned `SimulationResult`.
+#[derive(Default)]
+pub struct SimulationBuilder<'a> {
+    settings: Option<Settings>,
+    scenario: Option<Scenario>,
+    seed: Option<u64>,
+    on_block: Option<BlockObserver<'a>>,
+    adversary: Option<Box<dyn Adversary + 'a>>,
+}
+
+impl<'a> SimulationBuilder<'a> {
+    pub fn settings(mut self, settings: Settings) -> Self {
+        self.settings = Some(settings);
+        self
+    }
+
+    pub fn scenario(mut self, scenario: Scenario) -> Self {
+        self.scenario = Some(scenario);
+        self
+    }
+
+    // Overrides `Settings::seed`
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    // Called after every block is produced, e.g. to drive a progress bar
+    pub fn on_block<F: FnMut(&Block) + 'a>(mut self, on_block: F) -> Self {
+        self.on_block = Some(Box::new(on_block));
+        self
+    }
+
+    // Replaces the built-in policy chosen by `Settings::adversary`
+    pub fn adversary<A: Adversary + 'a>(mut self, adversary: A) -> Self {
+        self.adversary = Some(Box::new(adversary));
+        self
+    }
+
+    pub fn run(self) -> Result<SimulationResult, SimulationError> {
+        let mut settings = self.settings.ok_or(SimulationError::MissingSettings)?;
+        let scenario = self.scenario.ok_or(SimulationError::MissingScenario)?;
+        validate(&settings)?;
+
+        // Unseeded runs draw a seed so the result still records how to reproduce it
+        let seed = self
+            .seed
+            .or(settings.seed)
+            .unwrap_or_else(|| rand::thread_rng().gen());
+        settings.seed = Some(seed);
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        let mut on_block = self.on_block.unwrap_or_else(|| Box::new(|_: &Block| {}));
+        let mut adversary = self
+            .adversary
+            .unwrap_or_else(|| adversary::from_settings(&settings.adversary));
+        let mut result = match scenario {
+            Scenario::Basic => scenarios::basic::run(&settings, &mut rng, &mut on_block),
+            Scenario::VoteCensorship => {
+                scenarios::vote_censorship::run(&settings, &mut rng, &mut on_block)
+            }
+            Scenario::DataWithholding => {
+                scenarios::data_withholding::run(&settings, &mut rng, &mut on_block)
+            }
+            Scenario::SmartDataWithholding => scenarios::smart_data_withholding::run(
+                &settings,
+                &mut rng,
+                adversary.as_mut(),
+                &mut on_block,
+            ),
+            Scenario::SelectiveWithholding => {
+                scenarios::selective_withholding::run(&settings, &mut rng, &mut on_block)
+            }
+            Scenario::Scheduled => {
+                scenarios::scheduled::run(&settings, &mut rng, adversary.as_mut(), &mut on_block)
+            }
+            Scenario::SelfishMining => {
+                scenarios::selfish_mining::run(&settings, &mut rng, &mut on_block)
+            }
+            Scenario::Equivocation => {
+                scenarios::equivocation::run(&settings, &mut rng, &mut on_block)
+            }
+            Scenario::Congestion => scenarios::congestion::run(&settings, &mut rng, &mut on_block),
+            Scenario::Spam => scenarios::spam::run(&settings, &mut rng, &mut on_block),
+            Scenario::FeeMarket => scenarios::fee_market::run(&settings, &mut rng, &mut on_block),
+        };
+        integrity::seal_chain(&mut result.blocks);
+        bandwidth::warn_on_caps(&result.blocks, &result.blobs, &result.settings);
+        result.recovery = recovery::recovery_metrics(&result);
+        Ok(result)
+    }
+}
+
+fn validate(settings: &Settings) -> Result<(), SimulationError> {
+    if settings.total_nodes == 0 {
+        return Err(SimulationError::InvalidSettings(
+            "total_nodes must be positive".to_string(),
+        ));
+    }
+    if settings.malicious_nodes > settings.total_nodes {
+        return Err(SimulationError::InvalidSettings(format!(
+            "malicious_nodes ({}) exceeds total_nodes ({})",
+            settings.malicious_nodes, settings.total_nodes
+        )));
+    }
+    if settings.nodes_per_block > settings.total_nodes {
+        return Err(SimulationError::InvalidSettings(format!(
+            "nodes_per_block ({}) exceeds total_nodes ({})",
+            settings.nodes_per_block, settings.total_nodes
+        )));
+    }
+    if settings.nodes_per_block == 0 {
+        return Err(SimulationError::InvalidSettings(
+            "nodes_per_block must be at least 1".to_string(),
+        ));
+    }
+    if settings.confirmation_depth == 0 {
+        return Err(SimulationError::InvalidSettings(
+            "confirmation_depth must be at least 1".to_string(),
+        ));
+    }
+    if settings.max_blobs_per_block == Some(0) {
+        return Err(SimulationError::InvalidSettings(
+            "max_blobs_per_block must be at least 1 when set".to_string(),
+        ));
+    }
+    match settings.blob_arrival {
+        BlobArrival::Fixed { rate } | BlobArrival::Poisson { rate }
+            if !(rate >= 0.0 && rate.is_finite()) =>
+        {
+            return Err(SimulationError::InvalidSettings(format!(
+                "blob_arrival rate ({}) must be a non-negative number",
+                rate
+            )));
+        }
+        _ => {}
+    }
+    if settings.malicious_power_block > 100 {
+        return Err(SimulationError::InvalidSettings(format!(
+            "malicious_power_block ({}) is a percentage and must be at most 100",
+            settings.malicious_power_block
+        )));
+    }
+    let equivocation = &settings.equivocation;
+    if !(0.0..=1.0).contains(&equivocation.rate) {
+        return Err(SimulationError::InvalidSettings(format!(
+            "equivocation.rate ({}) must be between 0 and 1",
+            equivocation.rate
+        )));
+    }
+    match equivocation.penalty {
+        SlashingPenalty::WeightReduction { factor: value }
+        | SlashingPenalty::RewardLoss { fraction: value }
+            if !(0.0..=1.0).contains(&value) =>
+        {
+            return Err(SimulationError::InvalidSettings(format!(
+                "equivocation.penalty ({}) must be between 0 and 1",
+                value
+            )));
+        }
+        _ => {}
+    }
+    if !(settings.safety.target > 0.0 && settings.safety.target < 1.0) {
+        return Err(SimulationError::InvalidSettings(format!(
+            "safety.target ({}) must be between 0 and 1",
+            settings.safety.target
+        )));
+    }
+    if let Some(power) = settings.safety.power_levels.iter().find(|&&p| p > 100) {
+        return Err(SimulationError::InvalidSettings(format!(
+            "safety.power_levels entry ({}) is a percentage and must be at most 100",
+            power
+        )));
+    }
+    match settings.bandwidth.blob_size {
+        BlobSize::Uniform { min, max } if min > max => {
+            return Err(SimulationError::InvalidSettings(format!(
+                "blob_size min ({}) exceeds max ({})",
+                min, max
+            )));
+        }
+        BlobSize::LogNormal { mean, std_dev }
+            if !(mean > 0.0 && mean.is_finite() && std_dev >= 0.0 && std_dev.is_finite()) =>
+        {
+            return Err(SimulationError::InvalidSettings(
+                "blob_size mean must be positive and std_dev non-negative".to_string(),
+            ));
+        }
+        _ => {}
+    }
+    match settings.stake {
+        StakeDistribution::Uniform { min, max }
+            if !(min >= 0.0 && min < max && max.is_finite()) =>
+        {
+            return Err(SimulationError::InvalidSettings(format!(
+                "stake range {}..{} is empty, negative or not finite",
+                min, max
+            )));
+        }
+        StakeDistribution::Pareto { scale, shape }
+            if !(scale > 0.0 && scale.is_finite() && shape > 0.0 && shape.is_finite()) =>
+        {
+            return Err(SimulationError::InvalidSettings(
+                "stake scale and shape must be positive and finite".to_string(),
+            ));
+        }
+        _ => {}
+    }
+    if !(0.0..=1.0).contains(&settings.selective.serve_fraction) {
+        return Err(SimulationError::InvalidSettings(format!(
+            "selective.serve_fraction ({}) must be between 0 and 1",
+            settings.selective.serve_fraction
+        )));
+    }
+    let spam = &settings.spam;
+    if let Some(rate) = std::iter::once(&spam.rate)
+        .chain(&spam.rates)
+        .find(|rate| !(**rate >= 0.0 && rate.is_finite()))
+    {
+        return Err(SimulationError::InvalidSettings(format!(
+            "spam rate ({}) must be a non-negative number",
+            rate
+        )));
+    }
+    if !(0.0..=1.0).contains(&spam.unavailable) {
+        return Err(SimulationError::InvalidSettings(format!(
+            "spam.unavailable ({}) must be between 0 and 1",
+            spam.unavailable
+        )));
+    }
+    if !(spam.fee >= 0.0 && spam.fee.is_finite()) {
+        return Err(SimulationError::InvalidSettings(format!(
+            "spam.fee ({}) must be a non-negative number",
+            spam.fee
+        )));
+    }
+    if spam
+        .rate_limit
+        .as_ref()
+        .is_some_and(|limit| limit.window == 0)
+    {
+        return Err(SimulationError::InvalidSettings(
+            "spam.rate_limit window must be at least one block".to_string(),
+        ));
+    }
+    let fee_market = &settings.fee_market;
+    if fee_market.target == 0 {
+        return Err(SimulationError::InvalidSettings(
+            "fee_market.target must be at least one blob".to_string(),
+        ));
+    }
+    for (name, range) in [
+        ("fee_market.max_fee", &fee_market.max_fee),
+        ("fee_market.tip", &fee_market.tip),
+    ] {
+        if !(range.min >= 0.0 && range.min <= range.max && range.max.is_finite()) {
+            return Err(SimulationError::InvalidSettings(format!(
+                "{} range {}..{} is empty, negative or not finite",
+                name, range.min, range.max
+            )));
+        }
+    }
+    // A zero floor would let the multiplicative update park the base fee at zero
+    if !(fee_market.min_base_fee > 0.0 && fee_market.min_base_fee.is_finite()) {
+        return Err(SimulationError::InvalidSettings(format!(
+            "fee_market.min_base_fee ({}) must be positive and finite",
+            fee_market.min_base_fee
+        )));
+    }
+    if !(fee_market.initial_base_fee >= 0.0 && fee_market.initial_base_fee.is_finite()) {
+        return Err(SimulationError::InvalidSettings(format!(
+            "fee_market.initial_base_fee ({}) must be non-negative and finite",
+            fee_market.initial_base_fee
+        )));
+    }
+    if !(0.0..=1.0).contains(&fee_market.max_change) {
+        return Err(SimulationError::InvalidSettings(format!(
+            "fee_market.max_change ({}) must be between 0 and 1",
+            fee_market.max_change
+        )));
+    }
+    if !(0.0..=1.0).contains(&fee_market.censored_share) {
+        return Err(SimulationError::InvalidSettings(format!(
+            "fee_market.censored_share ({}) must be between 0 and 1",
+            fee_market.censored_share
+        )));
+    }
+    let das = &settings.das;
+    if das.chunks == 0 || das.data_chunks == 0 || das.data_chunks > das.chunks {
+        return Err(SimulationError::InvalidSettings(format!(
+            "das.data_chunks ({}) must be between 1 and das.chunks ({})",
+            das.data_chunks, das.chunks
+        )));
+    }
+    if let DasLayout::TwoD { side } = das.layout {
+        if side < 2 || side % 2 != 0 {
+            return Err(SimulationError::InvalidSettings(format!(
+                "das.layout side ({}) must be even and at least 2",
+                side
+            )));
+        }
+    }
+    if das
+        .withheld_chunks
+        .is_some_and(|withheld| withheld > das.total_chunks())
+    {
+        return Err(SimulationError::InvalidSettings(format!(
+            "das.withheld_chunks exceeds the {} chunks per blob",
+            das.total_chunks()
+        )));
+    }
+    // Every phase after the first needs something that starts it
+    for (previous, phase) in settings
+        .schedule
+        .iter()
+        .zip(settings.schedule.iter().skip(1))
+    {
+        if phase.start.is_none() && phase.start_when.is_none() && previous.blocks.is_none() {
+            return Err(SimulationError::InvalidSettings(format!(
+                "schedule phase '{}' has no start, start_when or preceding phase length",
+                phase.name
+            )));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::integrity::validate_chain;
+
+    #[test]
+    fn builder_runs_are_reproducible() {
+        let run = |seed| {
+            Simulation::builder()
+                .settings(Settings::for_tests())
+                .scenario(Scenario::VoteCensorship)
+                .seed(seed)
+                .run()
+                .unwrap()
+        };
+        let result = run(11);
+
+        assert_eq!(result.scenario, Scenario::VoteCensorship);
+        assert_eq!(result.settings.seed, Some(11));
+        assert_eq!(result.blocks.len(), 20);
+        assert_eq!(result.honest_nodes.len(), 15);
+        assert_eq!(validate_chain(&result.blocks), Ok(()));
+        // Compared as JSON values, since node sets and maps iterate in arbitrary order
+        let again = run(11);
+        assert_eq!(again.honest_nodes, result.honest_nodes);
+        assert_eq!(
+            serde_json::to_value((&again.blocks, &again.blobs, &again.votes)).unwrap(),
+            serde_json::to_value((&result.blocks, &result.blobs, &result.votes)).unwrap()
+        );
+    }
+
+    #[test]
+    fn stake_must_be_finite() {
+        for stake in [
+            StakeDistribution::Uniform {
+                min: 1.0,
+                max: f64::INFINITY,
+            },
+            StakeDistribution::Uniform {
+                min: f64::NAN,
+                max: 2.0,
+            },
+            StakeDistribution::Pareto {
+                scale: f64::NAN,
+                shape: 1.0,
+            },
+            StakeDistribution::Pareto {
+                scale: 1.0,
+                shape: f64::INFINITY,
+            },
+        ] {
+            let mut settings = Settings::for_tests();
+            settings.stake = stake.clone();
+            assert!(
+                matches!(
+                    validate(&settings),
+                    Err(SimulationError::InvalidSettings(_))
+                ),
+                "{:?} accepted",
+                stake
+            );
+        }
+    }
+
+    #[test]
+    fn spam_fee_must_be_a_number() {
+        for fee in [-1.0, f64::NAN, f64::INFINITY] {
+            let mut settings = Settings::for_tests();
+            settings.spam.fee = fee;
+            assert!(
+                matches!(
+                    validate(&settings),
+                    Err(SimulationError::InvalidSettings(_))
+                ),
+                "spam.fee {} accepted",
+                fee
+            );
+        }
+    }
+
+    #[test]
+    fn fee_market_bounds_must_be_finite() {
+        let mut settings = Settings::for_tests();
+        settings.fee_market.max_fee.max = f64::INFINITY;
+        assert!(validate(&settings).is_err());
+
+        let mut settings = Settings::for_tests();
+        settings.fee_market.tip.max = f64::NAN;
+        assert!(validate(&settings).is_err());
+
+        // The floor keeps the multiplicative update away from zero
+        let mut settings = Settings::for_tests();
+        settings.fee_market.min_base_fee = 0.0;
+        assert!(validate(&settings).is_err());
+    }
+
+    #[test]
+    fn builder_reports_missing_and_invalid_settings() {
+        let error = Simulation::builder()
+            .scenario(Scenario::Basic)
+            .run()
+            .unwrap_err();
+        assert_eq!(error, SimulationError::MissingSettings);
+
+        let error = Simulation::builder()
+            .settings(Settings::for_tests())
+            .run()
+            .unwrap_err();
+        assert_eq!(error, SimulationError::MissingScenario);
+
+        let mut settings = Settings::for_tests();
+        settings.malicious_nodes = 21;
+        let error = Simulation::builder()
+            .settings(settings)
+            .scenario(Scenario::Basic)
+            .run()
+            .unwrap_err();
+        assert_eq!(
+            error,
+            SimulationError::InvalidSettings(
+                "malicious_nodes (21) exceeds total_nodes (20)".to_string()
+            )
+        );
+
+        // Reported as such rather than as a committee larger than the network
+        let mut settings = Settings::for_tests();
+        settings.total_nodes = 0;
+        let error = Simulation::builder()
+            .settings(settings)
+            .scenario(Scenario::Basic)
+            .run()
+            .unwrap_err();
+        assert_eq!(
+            error,
+            SimulationError::InvalidSettings("total_nodes must be positive".to_string())
+        );
+
+        let mut settings = Settings::for_tests();
+        settings.max_blobs_per_block = Some(0);
+        let error = Simulation::builder()
+            .settings(settings)
+            .scenario(Scenario::Basic)
+            .run()
+            .unwrap_err();
+        assert!(matches!(error, SimulationError::InvalidSettings(message)
+            if message.contains("max_blobs_per_block")));
+    }
+}
@@ -0,0 +1,44 @@
+use crate::models::AdversaryMetrics;
+use prettytable::{format, Cell, Row, Table};
+
+// How often the adaptive adversary took each action, over open blobs and blocks
+pub fn create_adversary_table(metrics: &AdversaryMetrics) -> Table {
+    let decisions =
+        metrics.vote_yes + metrics.vote_no + metrics.abstain + metrics.withhold + metrics.censor;
+    let share = |count: usize| {
+        if decisions == 0 {
+            0.0
+        } else {
+            count as f64 / decisions as f64 * 100.0
+        }
+    };
+
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_BOX_CHARS);
+    table.add_row(Row::new(vec![
+        Cell::new(&format!("Adversary: {}", metrics.policy)),
+        Cell::new("Decisions"),
+        Cell::new("Share"),
+    ]));
+
+    for (name, count) in [
+        ("Vote Yes", metrics.vote_yes),
+        ("Vote No", metrics.vote_no),
+        ("Abstain", metrics.abstain),
+        ("Withhold", metrics.withhold),
+        ("Censor", metrics.censor),
+    ] {
+        table.add_row(Row::new(vec![
+            Cell::new(name),
+            Cell::new(&count.to_string()),
+            Cell::new(&format!("{:.2}%", share(count))),
+        ]));
+    }
+    table.add_row(Row::new(vec![
+        Cell::new("Honest votes censored"),
+        Cell::new(&metrics.censored_votes.to_string()),
+        Cell::new(""),
+    ]));
+
+    table
+}
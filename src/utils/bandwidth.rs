@@ -0,0 +1,220 @@
+use crate::models::{Blob, Block, Settings};
+use crate::utils::throughput::THROUGHPUT_WINDOW;
+use prettytable::{format, Cell, Row, Table};
+use std::collections::HashMap;
+use std::io::{BufWriter, Write};
+use tracing::warn;
+
+// Bytes moved in one block. Every node receives the full block (header, included
+// blob payloads and included votes) and each committee member also sends one vote
+// per blob open for voting, whether or not the proposer includes it.
+#[derive(Debug, Clone)]
+pub struct BlockBandwidth {
+    pub block: usize,
+    pub block_bytes: u64,
+    pub mean_node_bytes: f64,
+    // Busiest node: a committee member, or any node when the committee is empty
+    pub max_node_bytes: u64,
+    pub network_bytes: u64,
+    // Chain data each node has stored by the end of the block
+    pub storage_bytes: u64,
+}
+
+pub fn bandwidth_series(
+    blocks: &[Block],
+    blobs: &HashMap<usize, Blob>,
+    settings: &Settings,
+) -> Vec<BlockBandwidth> {
+    let vote_bytes = settings.bandwidth.vote_bytes as u64;
+    let header_bytes = settings.bandwidth.header_bytes as u64;
+    let total_nodes = settings.total_nodes.max(1) as u64;
+    let mut storage_bytes = 0;
+
+    blocks
+        .iter()
+        .map(|block| {
+            let payload_bytes: u64 = block
+                .blobs
+                .iter()
+                .filter_map(|blob_id| blobs.get(blob_id))
+                .map(|blob| blob.size as u64)
+                .sum();
+            let included_votes = (block.votes.values().flatten().flatten().count()
+                + block.buffered_votes.values().map(Vec::len).sum::<usize>())
+                as u64;
+            let block_bytes = header_bytes + payload_bytes + included_votes * vote_bytes;
+
+            let votes_per_member = block.votes.len() as u64;
+            let committee = block.selected_nodes.len() as u64;
+            let network_bytes =
+                total_nodes * block_bytes + committee * votes_per_member * vote_bytes;
+            let max_node_bytes = if committee > 0 {
+                block_bytes + votes_per_member * vote_bytes
+            } else {
+                block_bytes
+            };
+            storage_bytes += block_bytes;
+
+            BlockBandwidth {
+                block: block.number,
+                block_bytes,
+                mean_node_bytes: network_bytes as f64 / total_nodes as f64,
+                max_node_bytes,
+                network_bytes,
+                storage_bytes,
+            }
+        })
+        .collect()
+}
+
+// Blocks in which the busiest node and the whole network went over their caps
+pub fn cap_violations(series: &[BlockBandwidth], settings: &Settings) -> (Vec<usize>, Vec<usize>) {
+    let over = |cap: Option<u64>, bytes: fn(&BlockBandwidth) -> u64| -> Vec<usize> {
+        match cap {
+            Some(cap) => series
+                .iter()
+                .filter(|point| bytes(point) > cap)
+                .map(|point| point.block)
+                .collect(),
+            None => Vec::new(),
+        }
+    };
+    (
+        over(settings.bandwidth.node_cap_bytes, |point| {
+            point.max_node_bytes
+        }),
+        over(settings.bandwidth.network_cap_bytes, |point| {
+            point.network_bytes
+        }),
+    )
+}
+
+// Logs a warning for each configured cap that the run exceeded
+pub fn warn_on_caps(blocks: &[Block], blobs: &HashMap<usize, Blob>, settings: &Settings) {
+    let series = bandwidth_series(blocks, blobs, settings);
+    let (node_over, network_over) = cap_violations(&series, settings);
+    if let Some(&first) = node_over.first() {
+        warn!(
+            cap = settings.bandwidth.node_cap_bytes,
+            blocks = node_over.len(),
+            first_block = first,
+            "per-node bandwidth cap exceeded"
+        );
+    }
+    if let Some(&first) = network_over.first() {
+        warn!(
+            cap = settings.bandwidth.network_cap_bytes,
+            blocks = network_over.len(),
+            first_block = first,
+            "network bandwidth cap exceeded"
+        );
+    }
+}
+
+pub fn create_bandwidth_table(
+    blocks: &[Block],
+    blobs: &HashMap<usize, Blob>,
+    settings: &Settings,
+) -> Table {
+    let series = bandwidth_series(blocks, blobs, settings);
+    let (node_over, network_over) = cap_violations(&series, settings);
+
+    let mean = |bytes: fn(&BlockBandwidth) -> f64| {
+        if series.is_empty() {
+            0.0
+        } else {
+            series.iter().map(bytes).sum::<f64>() / series.len() as f64
+        }
+    };
+    let max = |bytes: fn(&BlockBandwidth) -> u64| series.iter().map(bytes).max().unwrap_or(0);
+    let cap = |cap: Option<u64>, over: &[usize]| match cap {
+        Some(cap) => format!("{} ({} blocks over)", cap, over.len()),
+        None => "none".to_string(),
+    };
+
+    let rows = [
+        (
+            "Mean block size (bytes)",
+            format!("{:.0}", mean(|point| point.block_bytes as f64)),
+        ),
+        (
+            "Max block size (bytes)",
+            max(|point| point.block_bytes).to_string(),
+        ),
+        (
+            "Mean per-node bandwidth per block (bytes)",
+            format!("{:.0}", mean(|point| point.mean_node_bytes)),
+        ),
+        (
+            "Max per-node bandwidth per block (bytes)",
+            max(|point| point.max_node_bytes).to_string(),
+        ),
+        (
+            "Mean network bandwidth per block (bytes)",
+            format!("{:.0}", mean(|point| point.network_bytes as f64)),
+        ),
+        (
+            "Max network bandwidth per block (bytes)",
+            max(|point| point.network_bytes).to_string(),
+        ),
+        (
+            "Storage per node at end of run (bytes)",
+            series
+                .last()
+                .map_or(0, |point| point.storage_bytes)
+                .to_string(),
+        ),
+        (
+            "Per-node cap (bytes per block)",
+            cap(settings.bandwidth.node_cap_bytes, &node_over),
+        ),
+        (
+            "Network cap (bytes per block)",
+            cap(settings.bandwidth.network_cap_bytes, &network_over),
+        ),
+    ];
+
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_BOX_CHARS);
+    table.add_row(Row::new(vec![Cell::new("Bandwidth"), Cell::new("Value")]));
+    for (name, value) in rows {
+        table.add_row(Row::new(vec![Cell::new(name), Cell::new(&value)]));
+    }
+
+    table
+}
+
+// Per-block bandwidth series for plotting, with a rolling mean of per-node bandwidth
+pub fn create_bandwidth_csv<W: Write>(
+    blocks: &[Block],
+    blobs: &HashMap<usize, Blob>,
+    settings: &Settings,
+    writer: &mut BufWriter<W>,
+) -> std::io::Result<()> {
+    writeln!(
+        writer,
+        "Block,Block Bytes,Mean Node Bytes,Rolling Mean Node Bytes,Max Node Bytes,Network Bytes,Storage Per Node Bytes"
+    )?;
+
+    let series = bandwidth_series(blocks, blobs, settings);
+    let mut window_sum = 0.0;
+    for (i, point) in series.iter().enumerate() {
+        window_sum += point.mean_node_bytes;
+        if i >= THROUGHPUT_WINDOW {
+            window_sum -= series[i - THROUGHPUT_WINDOW].mean_node_bytes;
+        }
+        writeln!(
+            writer,
+            "{},{},{:.1},{:.1},{},{},{}",
+            point.block,
+            point.block_bytes,
+            point.mean_node_bytes,
+            window_sum / (i + 1).min(THROUGHPUT_WINDOW) as f64,
+            point.max_node_bytes,
+            point.network_bytes,
+            point.storage_bytes
+        )?;
+    }
+    writer.flush()?;
+    Ok(())
+}
@@ -0,0 +1,226 @@
+use crate::models::Settings;
+use crate::ENGINE_VERSION;
+use std::fs;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+const MANIFEST: &str = "manifest.txt";
+
+#[derive(Debug, Clone)]
+pub struct CacheEntry {
+    pub key: String,
+    pub scenario: String,
+    pub seed: u64,
+    pub engine_version: u32,
+    pub files: Vec<String>,
+}
+
+// On-disk store of scenario outputs, one directory per cache key
+pub struct ResultCache {
+    root: PathBuf,
+}
+
+impl ResultCache {
+    pub fn open<P: AsRef<Path>>(root: P) -> io::Result<Self> {
+        fs::create_dir_all(root.as_ref())?;
+        Ok(Self {
+            root: root.as_ref().to_path_buf(),
+        })
+    }
+
+    // Key over (scenario, resolved settings, seed, engine version); unseeded runs have none
+    pub fn key(scenario: &str, settings: &Settings) -> Option<String> {
+        settings.seed?;
+        let input = format!("{}|{}|{:?}", scenario, ENGINE_VERSION, settings);
+        Some(format!("{:016x}", fnv1a(input.as_bytes())))
+    }
+
+    pub fn contains(&self, key: &str) -> bool {
+        self.root.join(key).join(MANIFEST).is_file()
+    }
+
+    // Copies the cached outputs into `dest`; returns false on a miss
+    pub fn restore<P: AsRef<Path>>(&self, key: &str, dest: P) -> io::Result<bool> {
+        let entry = match self.read_entry(key)? {
+            Some(entry) => entry,
+            None => return Ok(false),
+        };
+        for file in &entry.files {
+            fs::copy(self.root.join(key).join(file), dest.as_ref().join(file))?;
+        }
+        Ok(true)
+    }
+
+    pub fn store<P: AsRef<Path>>(
+        &self,
+        key: &str,
+        scenario: &str,
+        settings: &Settings,
+        src: P,
+        files: &[String],
+    ) -> io::Result<()> {
+        let dir = self.root.join(key);
+        fs::create_dir_all(&dir)?;
+        for file in files {
+            fs::copy(src.as_ref().join(file), dir.join(file))?;
+        }
+
+        // The manifest is written last so a partially stored entry is never a hit
+        let mut writer = BufWriter::new(fs::File::create(dir.join(MANIFEST))?);
+        writeln!(writer, "scenario={}", scenario)?;
+        writeln!(writer, "seed={}", settings.seed.unwrap_or_default())?;
+        writeln!(writer, "engine_version={}", ENGINE_VERSION)?;
+        writeln!(writer, "files={}", files.join(";"))?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    pub fn entries(&self) -> io::Result<Vec<CacheEntry>> {
+        let mut entries = Vec::new();
+        for dir in fs::read_dir(&self.root)? {
+            let key = dir?.file_name().to_string_lossy().into_owned();
+            if let Some(entry) = self.read_entry(&key)? {
+                entries.push(entry);
+            }
+        }
+        entries.sort_by(|a, b| a.scenario.cmp(&b.scenario).then(a.key.cmp(&b.key)));
+        Ok(entries)
+    }
+
+    // Removes entries that can never be hit again: other engine versions,
+    // missing output files or no manifest at all. Returns how many were removed.
+    pub fn gc(&self) -> io::Result<usize> {
+        let mut removed = 0;
+        for dir in fs::read_dir(&self.root)? {
+            let path = dir?.path();
+            let key = path.file_name().unwrap().to_string_lossy().into_owned();
+            let stale = match self.read_entry(&key)? {
+                Some(entry) => {
+                    entry.engine_version != ENGINE_VERSION
+                        || entry.files.iter().any(|file| !path.join(file).is_file())
+                }
+                None => true,
+            };
+            if stale {
+                if path.is_dir() {
+                    fs::remove_dir_all(&path)?;
+                } else {
+                    fs::remove_file(&path)?;
+                }
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
+    fn read_entry(&self, key: &str) -> io::Result<Option<CacheEntry>> {
+        if !self.contains(key) {
+            return Ok(None);
+        }
+        let file = fs::File::open(self.root.join(key).join(MANIFEST))?;
+        let mut entry = CacheEntry {
+            key: key.to_string(),
+            scenario: String::new(),
+            seed: 0,
+            engine_version: 0,
+            files: Vec::new(),
+        };
+        for line in BufReader::new(file).lines() {
+            let line = line?;
+            match line.split_once('=') {
+                Some(("scenario", value)) => entry.scenario = value.to_string(),
+                Some(("seed", value)) => entry.seed = value.parse().unwrap_or_default(),
+                Some(("engine_version", value)) => {
+                    entry.engine_version = value.parse().unwrap_or_default()
+                }
+                Some(("files", value)) => {
+                    entry.files = value
+                        .split(';')
+                        .filter(|file| !file.is_empty())
+                        .map(str::to_string)
+                        .collect()
+                }
+                _ => {}
+            }
+        }
+        Ok(Some(entry))
+    }
+}
+
+// FNV-1a, so keys stay stable across toolchains unlike `DefaultHasher`
+fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for &byte in bytes {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seeded(seed: u64) -> Settings {
+        let mut settings = Settings::for_tests();
+        settings.seed = Some(seed);
+        settings
+    }
+
+    #[test]
+    fn fnv1a_matches_reference_values() {
+        assert_eq!(fnv1a(b""), 0xcbf29ce484222325);
+        assert_eq!(fnv1a(b"a"), 0xaf63dc4c8601ec8c);
+    }
+
+    #[test]
+    fn key_depends_only_on_scenario_settings_and_seed() {
+        let key = ResultCache::key("basic", &seeded(1)).unwrap();
+        assert_eq!(key.len(), 16);
+        assert_eq!(ResultCache::key("basic", &seeded(1)), Some(key.clone()));
+        assert_ne!(ResultCache::key("basic", &seeded(2)), Some(key.clone()));
+        assert_ne!(ResultCache::key("spam", &seeded(1)), Some(key.clone()));
+
+        let mut settings = seeded(1);
+        settings.total_blocks += 1;
+        assert_ne!(ResultCache::key("basic", &settings), Some(key));
+
+        settings.seed = None;
+        assert_eq!(ResultCache::key("basic", &settings), None);
+    }
+
+    #[test]
+    fn stored_entry_is_restored_and_listed() {
+        let root = std::env::temp_dir().join(format!("sim_cache_test_{}", std::process::id()));
+        let src = root.join("src");
+        let dest = root.join("dest");
+        fs::create_dir_all(&src).unwrap();
+        fs::create_dir_all(&dest).unwrap();
+        fs::write(src.join("out.txt"), "result").unwrap();
+
+        let cache = ResultCache::open(root.join("cache")).unwrap();
+        let settings = seeded(3);
+        let key = ResultCache::key("basic", &settings).unwrap();
+        assert!(!cache.restore(&key, &dest).unwrap());
+
+        cache
+            .store(&key, "basic", &settings, &src, &["out.txt".to_string()])
+            .unwrap();
+        assert!(cache.restore(&key, &dest).unwrap());
+        assert_eq!(fs::read_to_string(dest.join("out.txt")).unwrap(), "result");
+
+        let entries = cache.entries().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(
+            (
+                entries[0].scenario.as_str(),
+                entries[0].seed,
+                entries[0].engine_version
+            ),
+            ("basic", 3, ENGINE_VERSION)
+        );
+        assert_eq!(cache.gc().unwrap(), 0);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,83 @@
+use crate::models::CongestionMetrics;
+use prettytable::{format, Cell, Row, Table};
+
+fn mean(delays: &[usize]) -> f64 {
+    if delays.is_empty() {
+        0.0
+    } else {
+        delays.iter().sum::<usize>() as f64 / delays.len() as f64
+    }
+}
+
+// Votes each kind of proposer included and left out, and whose votes they were
+pub fn create_congestion_table(metrics: &CongestionMetrics) -> Table {
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_BOX_CHARS);
+    table.add_row(Row::new(vec![
+        Cell::new("Proposer"),
+        Cell::new("Policy"),
+        Cell::new("Blocks"),
+        Cell::new("Congested Blocks"),
+        Cell::new("Honest Votes Included"),
+        Cell::new("Malicious Votes Included"),
+        Cell::new("Honest Votes Left Out"),
+        Cell::new("Malicious Votes Left Out"),
+        Cell::new("Honest Share of Left Out"),
+    ]));
+    for (name, policy, inclusion) in [
+        ("honest", &metrics.honest_policy, &metrics.honest_proposers),
+        (
+            "malicious",
+            &metrics.malicious_policy,
+            &metrics.malicious_proposers,
+        ),
+    ] {
+        table.add_row(Row::new(vec![
+            Cell::new(name),
+            Cell::new(policy),
+            Cell::new(&inclusion.blocks.to_string()),
+            Cell::new(&inclusion.congested_blocks.to_string()),
+            Cell::new(&inclusion.included_honest.to_string()),
+            Cell::new(&inclusion.included_malicious.to_string()),
+            Cell::new(&inclusion.left_out_honest.to_string()),
+            Cell::new(&inclusion.left_out_malicious.to_string()),
+            Cell::new(&format!(
+                "{:.2}%",
+                inclusion.honest_left_out_share() * 100.0
+            )),
+        ]));
+    }
+
+    table
+}
+
+// How long each side's votes waited in the pool and how many never made it
+pub fn create_pool_delay_table(metrics: &CongestionMetrics) -> Table {
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_BOX_CHARS);
+    table.add_row(Row::new(vec![
+        Cell::new("Voter"),
+        Cell::new("Votes Included"),
+        Cell::new("Mean Inclusion Delay"),
+        Cell::new("Max Inclusion Delay"),
+        Cell::new("Lost to Confirmation"),
+    ]));
+    for (name, delays, lost) in [
+        ("honest", &metrics.honest_delays, metrics.lost_honest),
+        (
+            "malicious",
+            &metrics.malicious_delays,
+            metrics.lost_malicious,
+        ),
+    ] {
+        table.add_row(Row::new(vec![
+            Cell::new(name),
+            Cell::new(&delays.len().to_string()),
+            Cell::new(&format!("{:.2}", mean(delays))),
+            Cell::new(&delays.iter().max().copied().unwrap_or(0).to_string()),
+            Cell::new(&lost.to_string()),
+        ]));
+    }
+
+    table
+}
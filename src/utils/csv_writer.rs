@@ -1,7 +1,10 @@
 use crate::models::Block;
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashSet};
 use std::io::{BufWriter, Write};
 
+// One row per block and open blob. The votes column lists every committee member in
+// position order as `node:decision(status)`, with `none` for a member whose vote was
+// left out, followed by the buffered votes as `node@cast_at:decision(status)`.
 pub fn create_results_csv<W: Write>(
     blocks: &[Block],
     honest_nodes: &HashSet<usize>,
@@ -10,67 +13,78 @@ pub fn create_results_csv<W: Write>(
     // Write header
     writeln!(
         writer,
-        "Block,Proposer(Status),Blob ID,Votes(Status),Honest Votes,Malicious Votes"
+        "Block,Proposer(Status),Blob ID,Votes(Status),Honest Votes,Malicious Votes,\
+         Honest Yes,Honest No,Malicious Yes,Malicious No"
     )?;
 
-    for block in blocks {
-        let proposer_status = if honest_nodes.contains(&block.proposer) {
+    let status = |node: &usize| {
+        if honest_nodes.contains(node) {
             "honest"
         } else {
             "malicious"
-        };
-        let proposer_str = format!("{}({})", block.proposer, proposer_status);
+        }
+    };
+    let decision = |vote: bool| if vote { "yes" } else { "no" };
+
+    for block in blocks {
+        let proposer_str = format!("{}({})", block.proposer, status(&block.proposer));
+
+        // Sorted by blob id so seeded runs write identical files
+        let blob_ids: BTreeSet<&usize> = block
+            .votes
+            .keys()
+            .chain(block.buffered_votes.keys())
+            .collect();
 
-        for (blob_id, blob_votes) in &block.votes {
-            let mut honest_count = 0;
-            let mut malicious_count = 0;
+        for blob_id in blob_ids {
+            let blob_votes = block.votes.get(blob_id);
+            // Yes and No counts of honest and malicious voters
+            let mut counts = [[0usize; 2]; 2];
 
-            // Process regular votes
             let mut votes: Vec<String> = block
                 .selected_nodes
                 .iter()
                 .enumerate()
-                .map(|(i, &node)| {
-                    let is_honest = honest_nodes.contains(&node);
-                    let vote = blob_votes.get(i).cloned().flatten();
-
-                    if is_honest {
-                        if vote.is_some() {
-                            honest_count += 1;
-                        }
-                    } else if vote.is_some() {
-                        malicious_count += 1;
+                .map(|(i, node)| {
+                    let vote = blob_votes.and_then(|votes| votes.get(i).copied().flatten());
+                    if let Some(vote) = vote {
+                        counts[usize::from(!honest_nodes.contains(node))][usize::from(!vote)] += 1;
                     }
-
                     format!(
-                        "{}({})",
-                        if vote.is_some() { "1" } else { "0" },
-                        if is_honest { "honest" } else { "malicious" }
+                        "{}:{}({})",
+                        node,
+                        vote.map_or("none", decision),
+                        status(node)
                     )
                 })
                 .collect();
 
-            // Add buffered votes to the same counts
-            if let Some(buffered) = block.buffered_votes.get(blob_id) {
-                for vote in buffered {
-                    if vote.is_some() {
-                        honest_count += 1;
-                        votes.push("1(honest)".to_string());
-                    }
-                }
+            for vote in block.buffered_votes.get(blob_id).into_iter().flatten() {
+                counts[usize::from(!honest_nodes.contains(&vote.voter))]
+                    [usize::from(!vote.decision)] += 1;
+                votes.push(format!(
+                    "{}@{}:{}({})",
+                    vote.voter,
+                    vote.cast_at,
+                    decision(vote.decision),
+                    status(&vote.voter)
+                ));
             }
 
-            let votes_str = votes
-                .iter()
-                .filter(|v| !v.starts_with("0"))
-                .cloned()
-                .collect::<Vec<String>>()
-                .join(";");
-
+            let [[honest_yes, honest_no], [malicious_yes, malicious_no]] = counts;
             writeln!(
                 writer,
-                "{},{},{},\"{}\",{},{}",
-                block.number, proposer_str, blob_id, votes_str, honest_count, malicious_count
+                "{},{},{},\"{}\",{},{},{},{},{},{}",
+                block.number,
+                proposer_str,
+                blob_id,
+                votes.join(";"),
+                honest_yes + honest_no,
+                malicious_yes + malicious_no,
+                honest_yes,
+                honest_no,
+                malicious_yes,
+                malicious_no
             )?;
         }
     }
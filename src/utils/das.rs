@@ -0,0 +1,160 @@
+use crate::models::{Blob, DasMetrics, VoteRecord};
+use prettytable::{format, Cell, Row, Table};
+use std::collections::{BTreeMap, HashMap};
+
+// Withheld fractions and sample counts shown in the detection sweep
+const SWEEP_FRACTIONS: [f64; 9] = [0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8, 0.9];
+const SWEEP_SAMPLES: [usize; 7] = [1, 2, 4, 8, 16, 32, 64];
+
+// Probability that at least one of `samples` distinct chunks drawn out of `chunks`
+// is among the `withheld` ones
+pub fn detection_probability(chunks: usize, withheld: usize, samples: usize) -> f64 {
+    let samples = samples.min(chunks);
+    let all_served: f64 = (0..samples)
+        .map(|i| chunks.saturating_sub(withheld + i) as f64 / (chunks - i) as f64)
+        .product();
+    1.0 - all_served
+}
+
+// Observed detection rate of honest samplers against the closed-form expectation
+pub fn create_das_table(metrics: &DasMetrics) -> Table {
+    let rows = [
+        ("Chunks per blob", metrics.chunks.to_string()),
+        ("Samples per node", metrics.samples.to_string()),
+        (
+            "Withheld chunks",
+            format!(
+                "{} ({:.1}%)",
+                metrics.withheld_chunks,
+                metrics.withheld_chunks as f64 / metrics.chunks.max(1) as f64 * 100.0
+            ),
+        ),
+        ("Attacked blobs", metrics.attacked_blobs.to_string()),
+        (
+            "Honest votes on attacked blobs",
+            metrics.sampled_votes.to_string(),
+        ),
+        ("Detections (voted No)", metrics.detections.to_string()),
+        (
+            "Observed detection rate",
+            format!("{:.2}%", metrics.detection_rate() * 100.0),
+        ),
+        (
+            "Expected detection rate",
+            format!(
+                "{:.2}%",
+                detection_probability(metrics.chunks, metrics.withheld_chunks, metrics.samples)
+                    * 100.0
+            ),
+        ),
+        (
+            "Attacked blobs recoverable by honest nodes",
+            format!("{} / {}", metrics.recoverable_blobs, metrics.attacked_blobs),
+        ),
+    ];
+
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_BOX_CHARS);
+    table.add_row(Row::new(vec![
+        Cell::new("Data Availability Sampling"),
+        Cell::new("Value"),
+    ]));
+    for (name, value) in rows {
+        table.add_row(Row::new(vec![Cell::new(name), Cell::new(&value)]));
+    }
+
+    table
+}
+
+// Per-node detection probability by withheld fraction (rows) and sample count (columns)
+pub fn create_detection_sweep_table(chunks: usize) -> Table {
+    let samples: Vec<usize> = SWEEP_SAMPLES
+        .iter()
+        .copied()
+        .filter(|&samples| samples <= chunks)
+        .collect();
+
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_BOX_CHARS);
+
+    let mut header = vec![Cell::new("Withheld \\ Samples")];
+    header.extend(samples.iter().map(|s| Cell::new(&s.to_string())));
+    table.add_row(Row::new(header));
+
+    for fraction in SWEEP_FRACTIONS {
+        let withheld = (fraction * chunks as f64).round() as usize;
+        let mut row = vec![Cell::new(&format!("{:.0}%", fraction * 100.0))];
+        row.extend(samples.iter().map(|&s| {
+            Cell::new(&format!(
+                "{:.4}",
+                detection_probability(chunks, withheld, s)
+            ))
+        }));
+        table.add_row(Row::new(row));
+    }
+
+    table
+}
+
+// Honest vote outcomes and network-wide recoverability of each attacked blob
+pub fn create_reconstruction_table(blobs: &HashMap<usize, Blob>, votes: &[VoteRecord]) -> Table {
+    let mut outcomes: BTreeMap<usize, (usize, usize)> = BTreeMap::new();
+    for vote in votes.iter().filter(|vote| vote.voter_honest) {
+        let (yes, no) = outcomes.entry(vote.blob_id).or_default();
+        if vote.decision {
+            *yes += 1;
+        } else {
+            *no += 1;
+        }
+    }
+
+    let mut attacked: Vec<&Blob> = blobs
+        .values()
+        .filter(|blob| blob.recoverable.is_some())
+        .collect();
+    attacked.sort_by_key(|blob| blob.id);
+
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_BOX_CHARS);
+    table.add_row(Row::new(vec![
+        Cell::new("Blob ID"),
+        Cell::new("Withheld Chunks"),
+        Cell::new("Honest Yes"),
+        Cell::new("Honest No"),
+        Cell::new("Recoverable"),
+    ]));
+
+    for blob in attacked {
+        let (yes, no) = outcomes.get(&blob.id).copied().unwrap_or_default();
+        table.add_row(Row::new(vec![
+            Cell::new(&blob.id.to_string()),
+            Cell::new(&blob.withheld_chunks.len().to_string()),
+            Cell::new(&yes.to_string()),
+            Cell::new(&no.to_string()),
+            Cell::new(if blob.recoverable == Some(true) {
+                "yes"
+            } else {
+                "no"
+            }),
+        ]));
+    }
+
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detection_probability_matches_hypergeometric() {
+        assert_eq!(detection_probability(8, 0, 4), 0.0);
+        assert_eq!(detection_probability(8, 4, 1), 0.5);
+        // 1 - (4/8)(3/7)
+        assert!((detection_probability(8, 4, 2) - 11.0 / 14.0).abs() < 1e-12);
+        // Five samples cannot all avoid four withheld chunks out of eight
+        assert_eq!(detection_probability(8, 4, 5), 1.0);
+        // Samples beyond the chunk count are clamped
+        assert_eq!(detection_probability(4, 1, 10), 1.0);
+    }
+}
@@ -0,0 +1,246 @@
+use crate::models::{Block, Settings};
+use prettytable::{format, Cell, Row, Table};
+use std::io::{BufWriter, Write};
+
+// Swept value, committee size and unconfirmed blobs of one model point
+type SweepPoint = (usize, usize, f64);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VoteEncoding {
+    // Every vote carries its own signature, `bandwidth.vote_bytes` each
+    Individual,
+    // One entry per blob: blob id, participation and decision bitfields over the
+    // committee, and one aggregate signature
+    Aggregated,
+}
+
+impl VoteEncoding {
+    pub const ALL: [VoteEncoding; 2] = [VoteEncoding::Individual, VoteEncoding::Aggregated];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            VoteEncoding::Individual => "individual",
+            VoteEncoding::Aggregated => "aggregated",
+        }
+    }
+
+    // Bytes for `votes` votes on one blob from a committee of `committee` nodes
+    pub fn blob_bytes(&self, votes: usize, committee: usize, settings: &Settings) -> u64 {
+        let encoding = &settings.bandwidth.encoding;
+        match self {
+            VoteEncoding::Individual => (votes * settings.bandwidth.vote_bytes) as u64,
+            VoteEncoding::Aggregated if votes == 0 => 0,
+            VoteEncoding::Aggregated => {
+                (encoding.blob_id_bytes + 2 * committee.div_ceil(8) + encoding.signature_bytes)
+                    as u64
+            }
+        }
+    }
+
+    // Vote bytes a block carries. Buffered votes from earlier committees get their own
+    // aggregate entry per blob, with a bitfield sized to this block's committee.
+    pub fn block_bytes(&self, block: &Block, settings: &Settings) -> u64 {
+        let committee = block.selected_nodes.len();
+        let direct = block
+            .votes
+            .values()
+            .map(|votes| votes.iter().flatten().count());
+        let buffered = block.buffered_votes.values().map(Vec::len);
+        direct
+            .chain(buffered)
+            .map(|included| self.blob_bytes(included, committee, settings))
+            .sum()
+    }
+
+    // Steady-state block size with every committee member voting on `open_blobs`
+    // blobs and `rate` new blob payloads included per block
+    pub fn model_block_bytes(
+        &self,
+        committee: usize,
+        open_blobs: f64,
+        rate: f64,
+        settings: &Settings,
+    ) -> f64 {
+        settings.bandwidth.header_bytes as f64
+            + rate * settings.bandwidth.blob_size.mean()
+            + open_blobs * self.blob_bytes(committee, committee, settings) as f64
+    }
+
+    // Highest blob rate whose steady-state block stays within the block size limit;
+    // each blob stays open for `depth` blocks
+    pub fn max_blob_rate(&self, committee: usize, depth: usize, settings: &Settings) -> f64 {
+        let room = settings.bandwidth.encoding.block_size_limit as f64
+            - settings.bandwidth.header_bytes as f64;
+        let per_blob = settings.bandwidth.blob_size.mean()
+            + depth as f64 * self.blob_bytes(committee, committee, settings) as f64;
+        if room <= 0.0 || per_blob <= 0.0 {
+            0.0
+        } else {
+            room / per_blob
+        }
+    }
+}
+
+// Mean and max vote bytes per block of a run under each encoding
+pub fn create_encoding_table(blocks: &[Block], settings: &Settings) -> Table {
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_BOX_CHARS);
+    table.add_row(Row::new(vec![
+        Cell::new("Vote Encoding"),
+        Cell::new("Mean Vote Bytes per Block"),
+        Cell::new("Max Vote Bytes per Block"),
+    ]));
+    for encoding in VoteEncoding::ALL {
+        let bytes: Vec<u64> = blocks
+            .iter()
+            .map(|block| encoding.block_bytes(block, settings))
+            .collect();
+        let mean = if bytes.is_empty() {
+            0.0
+        } else {
+            bytes.iter().sum::<u64>() as f64 / bytes.len() as f64
+        };
+        table.add_row(Row::new(vec![
+            Cell::new(encoding.name()),
+            Cell::new(&format!("{:.0}", mean)),
+            Cell::new(&bytes.iter().max().copied().unwrap_or(0).to_string()),
+        ]));
+    }
+
+    table
+}
+
+// Steady-state block size under each encoding as one parameter is swept and the
+// others stay at their configured values
+pub fn create_block_size_tables(settings: &Settings) -> Vec<Table> {
+    let encoding = &settings.bandwidth.encoding;
+    let rate = settings.blob_arrival.mean();
+    let committee = settings.nodes_per_block;
+    let depth = settings.confirmation_depth;
+    let open = rate * depth as f64;
+
+    let sweeps: [(&str, Vec<SweepPoint>); 3] = [
+        (
+            "Committee Size",
+            encoding
+                .committee_sizes
+                .iter()
+                .map(|&size| (size, size, open))
+                .collect(),
+        ),
+        (
+            "Unconfirmed Blobs",
+            encoding
+                .open_blobs
+                .iter()
+                .map(|&blobs| (blobs, committee, blobs as f64))
+                .collect(),
+        ),
+        (
+            "Confirmation Depth",
+            encoding
+                .depths
+                .iter()
+                .map(|&depth| (depth, committee, rate * depth as f64))
+                .collect(),
+        ),
+    ];
+
+    sweeps
+        .into_iter()
+        .map(|(name, points)| {
+            let mut table = Table::new();
+            table.set_format(*format::consts::FORMAT_BOX_CHARS);
+            table.add_row(Row::new(vec![
+                Cell::new(name),
+                Cell::new("Individual Block Bytes"),
+                Cell::new("Aggregated Block Bytes"),
+                Cell::new("Individual / Aggregated"),
+            ]));
+            for (value, committee, open) in points {
+                let [individual, aggregated] = VoteEncoding::ALL
+                    .map(|encoding| encoding.model_block_bytes(committee, open, rate, settings));
+                table.add_row(Row::new(vec![
+                    Cell::new(&value.to_string()),
+                    Cell::new(&format!("{:.0}", individual)),
+                    Cell::new(&format!("{:.0}", aggregated)),
+                    Cell::new(&format!("{:.2}", individual / aggregated)),
+                ]));
+            }
+            table
+        })
+        .collect()
+}
+
+// Max sustainable blob rate per committee size at the configured depth
+pub fn create_capacity_table(settings: &Settings) -> Table {
+    let depth = settings.confirmation_depth;
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_BOX_CHARS);
+    table.add_row(Row::new(vec![
+        Cell::new("Committee Size"),
+        Cell::new(&format!("Individual Max Blobs per Block (depth {})", depth)),
+        Cell::new(&format!("Aggregated Max Blobs per Block (depth {})", depth)),
+    ]));
+    for &committee in &settings.bandwidth.encoding.committee_sizes {
+        let [individual, aggregated] =
+            VoteEncoding::ALL.map(|encoding| encoding.max_blob_rate(committee, depth, settings));
+        table.add_row(Row::new(vec![
+            Cell::new(&committee.to_string()),
+            Cell::new(&format!("{:.2}", individual)),
+            Cell::new(&format!("{:.2}", aggregated)),
+        ]));
+    }
+
+    table
+}
+
+// Max sustainable blob rate for every swept committee size and depth, for plotting
+pub fn create_capacity_csv<W: Write>(
+    settings: &Settings,
+    writer: &mut BufWriter<W>,
+) -> std::io::Result<()> {
+    writeln!(
+        writer,
+        "Committee Size,Confirmation Depth,Individual Max Blob Rate,Aggregated Max Blob Rate"
+    )?;
+    let encoding = &settings.bandwidth.encoding;
+    for &committee in &encoding.committee_sizes {
+        for &depth in &encoding.depths {
+            let [individual, aggregated] = VoteEncoding::ALL
+                .map(|encoding| encoding.max_blob_rate(committee, depth, settings));
+            writeln!(
+                writer,
+                "{},{},{:.4},{:.4}",
+                committee, depth, individual, aggregated
+            )?;
+        }
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn max_blob_rate_fills_the_block_size_limit() {
+        // 2 MiB blocks, 512-byte header and 128 KiB blobs open for 5 blocks
+        let mut settings = Settings::for_tests();
+
+        // 16 votes of 128 bytes per blob and block
+        let rate = VoteEncoding::Individual.max_blob_rate(16, 5, &settings);
+        assert!((rate - 2_096_640.0 / 141_312.0).abs() < 1e-9);
+        // One 108-byte entry: blob id, two 2-byte bitfields and the aggregate signature
+        assert_eq!(VoteEncoding::Aggregated.blob_bytes(16, 16, &settings), 108);
+        let rate = VoteEncoding::Aggregated.max_blob_rate(16, 5, &settings);
+        assert!((rate - 2_096_640.0 / 131_612.0).abs() < 1e-9);
+
+        settings.bandwidth.encoding.block_size_limit = 512;
+        assert_eq!(
+            VoteEncoding::Individual.max_blob_rate(16, 5, &settings),
+            0.0
+        );
+    }
+}
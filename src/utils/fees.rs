@@ -0,0 +1,218 @@
+use crate::models::{Blob, FeeMetrics, SimulationResult};
+use prettytable::{format, Cell, Row, Table};
+use std::collections::HashSet;
+use std::io::{BufWriter, Write};
+
+// Rows in the fee dynamics table besides the first and last block
+const FEE_SAMPLES: usize = 10;
+
+fn mean(values: impl Iterator<Item = f64>) -> f64 {
+    let (sum, count) = values.fold((0.0, 0), |(sum, count), value| (sum + value, count + 1));
+    if count == 0 {
+        0.0
+    } else {
+        sum / count as f64
+    }
+}
+
+// Base fee, proposer revenue and what censoring cost malicious proposers
+pub fn create_fee_table(result: &SimulationResult) -> Table {
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_BOX_CHARS);
+    let metrics = result.fees.clone().unwrap_or_default();
+    let settings = &result.settings;
+    table.add_row(Row::new(vec![
+        Cell::new(&format!("Fee market ({} policy)", metrics.policy)),
+        Cell::new("Value"),
+    ]));
+
+    let capacity = settings
+        .max_blobs_per_block
+        .unwrap_or(2 * settings.fee_market.target);
+    let initial = metrics.blocks.first().map_or(0.0, |record| record.base_fee);
+    let proposers = |honest: bool| {
+        metrics
+            .blocks
+            .iter()
+            .filter(|record| record.proposer_honest == honest)
+            .count()
+    };
+    let revenue = |honest: bool| {
+        let blocks = proposers(honest);
+        let revenue = metrics.revenue(honest);
+        let per_block = if blocks == 0 {
+            0.0
+        } else {
+            revenue / blocks as f64
+        };
+        format!("{:.2} ({:.2} per block)", revenue, per_block)
+    };
+    let pending: Vec<&Blob> = result
+        .blobs
+        .values()
+        .filter(|blob| blob.included_at.is_none())
+        .collect();
+    let priced_out = pending
+        .iter()
+        .filter(|blob| blob.max_fee < metrics.final_base_fee)
+        .count();
+
+    for (name, value) in [
+        (
+            "Target / max blobs per block",
+            format!("{} / {}", settings.fee_market.target, capacity),
+        ),
+        (
+            "Mean blobs per block",
+            format!(
+                "{:.2}",
+                mean(metrics.blocks.iter().map(|record| record.included as f64))
+            ),
+        ),
+        (
+            "Base fee (start / mean / end)",
+            format!(
+                "{:.4} / {:.4} / {:.4}",
+                initial,
+                mean(metrics.blocks.iter().map(|record| record.base_fee)),
+                metrics.final_base_fee
+            ),
+        ),
+        ("Honest proposer revenue", revenue(true)),
+        ("Malicious proposer revenue", revenue(false)),
+        (
+            "Burned",
+            format!(
+                "{:.2}",
+                metrics
+                    .blocks
+                    .iter()
+                    .map(|record| record.burned)
+                    .sum::<f64>()
+            ),
+        ),
+        (
+            "Revenue forgone by censoring",
+            format!("{:.2}", metrics.forgone_revenue),
+        ),
+        (
+            "Blobs left in the mempool (priced out)",
+            format!("{} ({})", pending.len(), priced_out),
+        ),
+    ] {
+        table.add_row(Row::new(vec![Cell::new(name), Cell::new(&value)]));
+    }
+
+    table
+}
+
+// Base fee, inclusion, backlog and revenue at evenly spaced blocks
+pub fn create_fee_dynamics_table(metrics: &FeeMetrics) -> Table {
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_BOX_CHARS);
+    table.add_row(Row::new(vec![
+        Cell::new("Block"),
+        Cell::new("Base Fee"),
+        Cell::new("Blobs Included"),
+        Cell::new("Mempool Backlog"),
+        Cell::new("Proposer Revenue"),
+        Cell::new("Proposer"),
+    ]));
+
+    let blocks = metrics.blocks.len();
+    let mut samples: Vec<usize> = (0..=FEE_SAMPLES)
+        .map(|sample| (blocks * sample / FEE_SAMPLES).max(1))
+        .filter(|&block| block <= blocks)
+        .collect();
+    samples.dedup();
+    for block in samples {
+        let record = &metrics.blocks[block - 1];
+        table.add_row(Row::new(vec![
+            Cell::new(&block.to_string()),
+            Cell::new(&format!("{:.4}", record.base_fee)),
+            Cell::new(&record.included.to_string()),
+            Cell::new(&record.backlog.to_string()),
+            Cell::new(&format!("{:.2}", record.revenue)),
+            Cell::new(if record.proposer_honest {
+                "honest"
+            } else {
+                "malicious"
+            }),
+        ]));
+    }
+
+    table
+}
+
+// Inclusion wait and fees paid by censored blobs next to every other blob
+pub fn create_fee_censorship_table(result: &SimulationResult) -> Table {
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_BOX_CHARS);
+    table.add_row(Row::new(vec![
+        Cell::new("Blobs"),
+        Cell::new("Submitted"),
+        Cell::new("Included"),
+        Cell::new("Mean Mempool Wait"),
+        Cell::new("Max Mempool Wait"),
+        Cell::new("Mean Fee Paid"),
+    ]));
+
+    let censored: HashSet<usize> = result
+        .fees
+        .as_ref()
+        .map(|metrics| metrics.censored_blobs.iter().copied().collect())
+        .unwrap_or_default();
+    for (name, is_censored) in [("censored", true), ("other", false)] {
+        let group: Vec<&Blob> = result
+            .blobs
+            .values()
+            .filter(|blob| censored.contains(&blob.id) == is_censored)
+            .collect();
+        let waits: Vec<usize> = group
+            .iter()
+            .filter_map(|blob| blob.included_at.map(|at| at - blob.submitted_at))
+            .collect();
+        table.add_row(Row::new(vec![
+            Cell::new(name),
+            Cell::new(&group.len().to_string()),
+            Cell::new(&waits.len().to_string()),
+            Cell::new(&format!(
+                "{:.2}",
+                mean(waits.iter().map(|&wait| wait as f64))
+            )),
+            Cell::new(&waits.iter().max().copied().unwrap_or(0).to_string()),
+            Cell::new(&format!(
+                "{:.4}",
+                mean(group.iter().filter_map(|blob| blob.fee_paid))
+            )),
+        ]));
+    }
+
+    table
+}
+
+// Fee market state after every block, for plotting
+pub fn create_fee_csv<W: Write>(
+    metrics: &FeeMetrics,
+    writer: &mut BufWriter<W>,
+) -> std::io::Result<()> {
+    writeln!(
+        writer,
+        "Block,Base Fee,Blobs Included,Mempool Backlog,Proposer Revenue,Burned,Proposer Honest"
+    )?;
+    for (index, record) in metrics.blocks.iter().enumerate() {
+        writeln!(
+            writer,
+            "{},{:.6},{},{},{:.6},{:.6},{}",
+            index + 1,
+            record.base_fee,
+            record.included,
+            record.backlog,
+            record.revenue,
+            record.burned,
+            record.proposer_honest
+        )?;
+    }
+    writer.flush()?;
+    Ok(())
+}
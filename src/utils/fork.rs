@@ -0,0 +1,71 @@
+use crate::models::ForkMetrics;
+use prettytable::{format, Cell, Row, Table};
+
+// Branches, reorgs and confirmation flips caused by the private-branch adversary
+pub fn create_fork_table(metrics: &ForkMetrics, canonical_blocks: usize) -> Table {
+    let share = |count: usize, total: usize| {
+        if total == 0 {
+            0.0
+        } else {
+            count as f64 / total as f64 * 100.0
+        }
+    };
+
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_BOX_CHARS);
+    table.add_row(Row::new(vec![
+        Cell::new(&format!("Forks ({} fork choice)", metrics.fork_choice)),
+        Cell::new("Value"),
+    ]));
+
+    for (name, value) in [
+        (
+            "Adversary block production",
+            format!("{:.2}%", metrics.malicious_power * 100.0),
+        ),
+        (
+            "Blocks produced",
+            format!(
+                "{} ({} by the adversary)",
+                metrics.produced_blocks, metrics.adversary_blocks
+            ),
+        ),
+        ("Canonical chain length", canonical_blocks.to_string()),
+        (
+            "Adversary share of canonical blocks",
+            format!(
+                "{:.2}%",
+                share(metrics.canonical_adversary_blocks, canonical_blocks)
+            ),
+        ),
+        ("Orphaned blocks", metrics.orphaned_blocks.to_string()),
+        ("Reorgs", metrics.reorg_depths.len().to_string()),
+        (
+            "Reorg depth (mean / max)",
+            format!(
+                "{:.2} / {}",
+                metrics.mean_reorg_depth(),
+                metrics.max_reorg_depth()
+            ),
+        ),
+        (
+            "Orphaned votes (honest / malicious)",
+            format!(
+                "{} / {}",
+                metrics.orphaned_votes_honest, metrics.orphaned_votes_malicious
+            ),
+        ),
+        (
+            "Honest votes left out of adversary blocks",
+            metrics.censored_votes.to_string(),
+        ),
+        (
+            "Blobs with flipped confirmation",
+            metrics.flipped_blobs.len().to_string(),
+        ),
+    ] {
+        table.add_row(Row::new(vec![Cell::new(name), Cell::new(&value)]));
+    }
+
+    table
+}
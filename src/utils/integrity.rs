@@ -0,0 +1,248 @@
+use crate::models::{blob_root, header_hash, vote_root, Block, BlockHeader, ZERO_HASH};
+use prettytable::{format, Cell, Row, Table};
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChainError {
+    MissingHeader {
+        block: usize,
+    },
+    // The header's number or proposer differs from the block it describes
+    HeaderMismatch {
+        block: usize,
+    },
+    BrokenLink {
+        block: usize,
+        expected: String,
+        found: String,
+    },
+    BlobRootMismatch {
+        block: usize,
+        expected: String,
+        found: String,
+    },
+    VoteRootMismatch {
+        block: usize,
+        expected: String,
+        found: String,
+    },
+    HashMismatch {
+        block: usize,
+    },
+}
+
+impl fmt::Display for ChainError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChainError::MissingHeader { block } => write!(f, "block {} has no header", block),
+            ChainError::HeaderMismatch { block } => {
+                write!(
+                    f,
+                    "block {}: header number or proposer differs from the block",
+                    block
+                )
+            }
+            ChainError::BrokenLink {
+                block,
+                expected,
+                found,
+            } => write!(
+                f,
+                "block {}: parent hash {} does not match previous block {}",
+                block, found, expected
+            ),
+            ChainError::BlobRootMismatch {
+                block,
+                expected,
+                found,
+            } => write!(
+                f,
+                "block {}: blob root {} does not match included blobs {}",
+                block, found, expected
+            ),
+            ChainError::VoteRootMismatch {
+                block,
+                expected,
+                found,
+            } => write!(
+                f,
+                "block {}: vote root {} does not match included votes {}",
+                block, found, expected
+            ),
+            ChainError::HashMismatch { block } => {
+                write!(f, "block {}: header hash does not match its fields", block)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ChainError {}
+
+// Links a chain by giving each block without a header one on top of the previous
+// block's hash; blocks already sealed on a branch keep theirs
+pub fn seal_chain(blocks: &mut [Block]) {
+    let mut parent_hash = ZERO_HASH.to_string();
+    for block in blocks {
+        if block.header.is_none() {
+            block.header = Some(BlockHeader::new(&parent_hash, block));
+        }
+        parent_hash = block.header.as_ref().unwrap().hash.clone();
+    }
+}
+
+// Checks every header against its block, the previous block's hash, and the blobs
+// and votes the block includes, stopping at the first inconsistency
+pub fn validate_chain(blocks: &[Block]) -> Result<(), ChainError> {
+    let mut parent_hash = ZERO_HASH;
+    for block in blocks {
+        let number = block.number;
+        let header = block
+            .header
+            .as_ref()
+            .ok_or(ChainError::MissingHeader { block: number })?;
+        if header.number != number || header.proposer != block.proposer {
+            return Err(ChainError::HeaderMismatch { block: number });
+        }
+        if header.parent_hash != parent_hash {
+            return Err(ChainError::BrokenLink {
+                block: number,
+                expected: parent_hash.to_string(),
+                found: header.parent_hash.clone(),
+            });
+        }
+        let root = blob_root(block);
+        if header.blob_root != root {
+            return Err(ChainError::BlobRootMismatch {
+                block: number,
+                expected: root,
+                found: header.blob_root.clone(),
+            });
+        }
+        let root = vote_root(block);
+        if header.vote_root != root {
+            return Err(ChainError::VoteRootMismatch {
+                block: number,
+                expected: root,
+                found: header.vote_root.clone(),
+            });
+        }
+        let hash = header_hash(
+            &header.parent_hash,
+            header.number,
+            header.proposer,
+            &header.blob_root,
+            &header.vote_root,
+        );
+        if header.hash != hash {
+            return Err(ChainError::HashMismatch { block: number });
+        }
+        parent_hash = &header.hash;
+    }
+    Ok(())
+}
+
+// Outcome of `validate_chain` with the chain's head hash
+pub fn create_integrity_table(blocks: &[Block]) -> Table {
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_BOX_CHARS);
+    table.add_row(Row::new(vec![
+        Cell::new("Chain integrity"),
+        Cell::new("Value"),
+    ]));
+
+    let head = blocks
+        .last()
+        .and_then(|block| block.header.as_ref())
+        .map_or("none".to_string(), |header| header.hash.clone());
+    let status = match validate_chain(blocks) {
+        Ok(()) => "valid".to_string(),
+        Err(e) => e.to_string(),
+    };
+    for (name, value) in [
+        ("Blocks", blocks.len().to_string()),
+        ("Head hash", head),
+        ("Status", status),
+    ] {
+        table.add_row(Row::new(vec![Cell::new(name), Cell::new(&value)]));
+    }
+
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn chain() -> Vec<Block> {
+        let mut blocks: Vec<Block> = (1..=3)
+            .map(|number| Block {
+                number,
+                proposer: number % 2,
+                blobs: vec![number - 1],
+                selected_nodes: vec![2, 3],
+                votes: HashMap::from([(number - 1, vec![Some(true), Some(false)])]),
+                buffered_votes: HashMap::new(),
+                header: None,
+            })
+            .collect();
+        seal_chain(&mut blocks);
+        blocks
+    }
+
+    #[test]
+    fn sealed_chain_is_valid() {
+        let blocks = chain();
+        assert_eq!(validate_chain(&blocks), Ok(()));
+        assert_eq!(blocks[0].header.as_ref().unwrap().parent_hash, ZERO_HASH);
+        assert_eq!(
+            blocks[2].header.as_ref().unwrap().parent_hash,
+            blocks[1].header.as_ref().unwrap().hash
+        );
+    }
+
+    #[test]
+    fn tampering_is_reported_at_the_first_bad_block() {
+        let mut blocks = chain();
+        blocks[1].votes.get_mut(&1).unwrap()[1] = Some(true);
+        assert!(matches!(
+            validate_chain(&blocks),
+            Err(ChainError::VoteRootMismatch { block: 2, .. })
+        ));
+
+        let mut blocks = chain();
+        blocks[2].blobs.push(7);
+        assert!(matches!(
+            validate_chain(&blocks),
+            Err(ChainError::BlobRootMismatch { block: 3, .. })
+        ));
+
+        let mut blocks = chain();
+        blocks[1].proposer = 5;
+        assert_eq!(
+            validate_chain(&blocks),
+            Err(ChainError::HeaderMismatch { block: 2 })
+        );
+
+        let mut blocks = chain();
+        blocks[0].header.as_mut().unwrap().hash = ZERO_HASH.to_string();
+        assert_eq!(
+            validate_chain(&blocks),
+            Err(ChainError::HashMismatch { block: 1 })
+        );
+
+        let mut blocks = chain();
+        blocks.remove(1);
+        assert!(matches!(
+            validate_chain(&blocks),
+            Err(ChainError::BrokenLink { block: 3, .. })
+        ));
+
+        let mut blocks = chain();
+        blocks[0].header = None;
+        assert_eq!(
+            validate_chain(&blocks),
+            Err(ChainError::MissingHeader { block: 1 })
+        );
+    }
+}
@@ -0,0 +1,12 @@
+use crate::models::SimulationResult;
+use std::io::{BufWriter, Write};
+
+// Lossless dump of a run; the CSV and table outputs can be regenerated from it
+pub fn create_results_json<W: Write>(
+    result: &SimulationResult,
+    writer: &mut BufWriter<W>,
+) -> std::io::Result<()> {
+    serde_json::to_writer(&mut *writer, result)?;
+    writer.flush()?;
+    Ok(())
+}
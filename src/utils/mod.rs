@@ -1,9 +1,31 @@
-use crate::models::{Blob, Block};
+use crate::models::{Blob, Block, CensorshipMetrics};
 use prettytable::{format, Cell, Row, Table};
 use std::collections::{HashMap, HashSet};
 use std::io::{BufWriter, Write};
 
+pub mod adversary;
+pub mod bandwidth;
+pub mod cache;
+pub mod congestion;
 pub mod csv_writer;
+pub mod das;
+pub mod encoding;
+pub mod fees;
+pub mod fork;
+pub mod integrity;
+pub mod json_writer;
+pub mod reader;
+pub mod recovery;
+pub mod safety;
+pub mod schedule;
+pub mod selective;
+pub mod slashing;
+pub mod spam;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+pub mod throughput;
+pub mod vote_latency;
+pub mod vote_validation;
 
 // General table for different scenarios
 pub fn create_results_table(blocks: &[Block], honest_nodes: &HashSet<usize>) -> Table {
@@ -27,7 +49,12 @@ pub fn create_results_table(blocks: &[Block], honest_nodes: &HashSet<usize>) ->
         };
         let proposer_str = format!("{}({})", block.proposer, proposer_status);
 
-        for (blob_id, blob_votes) in &block.votes {
+        // Sorted by blob id so seeded runs write identical files
+        let mut blob_ids: Vec<&usize> = block.votes.keys().collect();
+        blob_ids.sort();
+
+        for blob_id in blob_ids {
+            let blob_votes = &block.votes[blob_id];
             let mut honest_count = 0;
             let mut malicious_count = 0;
 
@@ -106,7 +133,7 @@ pub fn create_voting_summary_per_block(blocks: &[Block], honest_nodes: &HashSet<
         let mut block_malicious_votes = 0;
 
         // Process votes for each blob
-        for (_blob_id, blob_votes) in &block.votes {
+        for blob_votes in block.votes.values() {
             block_total_votes += blob_votes.iter().filter(|vote| vote.is_some()).count();
 
             // Count honest and malicious votes
@@ -170,11 +197,90 @@ pub fn create_voting_summary_per_blob(blobs: &HashMap<usize, Blob>) -> Table {
     table
 }
 
+// Run-level statistics, mirroring `print_statistics` in plot.py
+pub fn create_statistics_table(
+    blocks: &[Block],
+    blobs: &HashMap<usize, Blob>,
+    honest_nodes: &HashSet<usize>,
+) -> Table {
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_BOX_CHARS);
+
+    let honest_proposers = blocks
+        .iter()
+        .filter(|block| honest_nodes.contains(&block.proposer))
+        .count();
+    let confirmed_blobs = blobs.values().filter(|blob| blob.is_confirmed).count();
+    let total_honest: usize = blobs.values().map(|blob| blob.votes_honest).sum();
+    let total_malicious: usize = blobs.values().map(|blob| blob.votes_malicious).sum();
+    let total_votes = total_honest + total_malicious;
+    let honest_percentage = if total_votes == 0 {
+        0.0
+    } else {
+        total_honest as f64 / total_votes as f64 * 100.0
+    };
+
+    let rows = [
+        ("Total blocks", blocks.len().to_string()),
+        ("Honest proposers", honest_proposers.to_string()),
+        (
+            "Malicious proposers",
+            (blocks.len() - honest_proposers).to_string(),
+        ),
+        ("Total blobs", blobs.len().to_string()),
+        ("Confirmed blobs", confirmed_blobs.to_string()),
+        ("Total honest votes", total_honest.to_string()),
+        ("Total malicious votes", total_malicious.to_string()),
+        ("Total votes", total_votes.to_string()),
+        (
+            "Honest vote percentage",
+            format!("{:.2}%", honest_percentage),
+        ),
+    ];
+
+    table.add_row(Row::new(vec![Cell::new("Statistic"), Cell::new("Value")]));
+    for (name, value) in rows {
+        table.add_row(Row::new(vec![Cell::new(name), Cell::new(&value)]));
+    }
+
+    table
+}
+
+pub fn create_censorship_table(metrics: &CensorshipMetrics) -> Table {
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_BOX_CHARS);
+
+    let rows = [
+        ("Censored votes", metrics.censored_votes.to_string()),
+        ("Included later", metrics.included_votes.to_string()),
+        (
+            "Lost (blob confirmed first)",
+            metrics.lost_votes.to_string(),
+        ),
+        ("Pending at end of run", metrics.pending_votes.to_string()),
+        (
+            "Mean inclusion delay (blocks)",
+            format!("{:.2}", metrics.mean_inclusion_delay()),
+        ),
+        (
+            "Max inclusion delay (blocks)",
+            metrics.max_inclusion_delay().to_string(),
+        ),
+    ];
+
+    table.add_row(Row::new(vec![Cell::new("Censorship"), Cell::new("Value")]));
+    for (name, value) in rows {
+        table.add_row(Row::new(vec![Cell::new(name), Cell::new(&value)]));
+    }
+
+    table
+}
+
 pub fn write_table_buffered<W: Write>(
     table: &Table,
     writer: &mut BufWriter<W>,
 ) -> std::io::Result<()> {
-    write!(writer, "{}", table.to_string())?;
+    write!(writer, "{}", table)?;
     writer.flush()?;
     Ok(())
 }
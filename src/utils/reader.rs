@@ -0,0 +1,262 @@
+use crate::models::{Blob, Block, BufferedVote, SimulationResult};
+use std::collections::{HashMap, HashSet};
+use std::io::{self, BufRead, BufReader, Read};
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+// Loads a run written by `create_results_json`
+pub fn read_results_json<R: Read>(reader: R) -> io::Result<SimulationResult> {
+    serde_json::from_reader(BufReader::new(reader)).map_err(io::Error::from)
+}
+
+// One data row of a `create_results_csv` file
+struct CsvRow {
+    line: usize,
+    block: usize,
+    proposer: usize,
+    proposer_honest: bool,
+    blob_id: usize,
+    votes: String,
+    honest_yes: usize,
+    malicious_no: usize,
+}
+
+fn parse_usize(line: usize, field: &str) -> io::Result<usize> {
+    field
+        .trim()
+        .parse::<usize>()
+        .map_err(|e| invalid_data(format!("line {}: '{}': {}", line, field, e)))
+}
+
+// Splits `value(status)` into the value and whether the status is honest
+fn split_status(line: usize, field: &str) -> io::Result<(&str, bool)> {
+    let (value, status) = field
+        .strip_suffix(')')
+        .and_then(|field| field.split_once('('))
+        .ok_or_else(|| {
+            invalid_data(format!(
+                "line {}: '{}': expected value(status)",
+                line, field
+            ))
+        })?;
+    match status {
+        "honest" => Ok((value, true)),
+        "malicious" => Ok((value, false)),
+        _ => Err(invalid_data(format!(
+            "line {}: unknown status '{}'",
+            line, status
+        ))),
+    }
+}
+
+fn read_rows<R: Read>(reader: R) -> io::Result<Vec<CsvRow>> {
+    let mut rows = Vec::new();
+
+    for (line_number, line) in BufReader::new(reader).lines().enumerate().skip(1) {
+        let line = line?;
+        let line_number = line_number + 1;
+        if line.is_empty() {
+            continue;
+        }
+
+        // The quoted votes column may contain `;` but never `,`
+        let fields: Vec<&str> = line.split(',').collect();
+        if fields.len() != 10 {
+            return Err(invalid_data(format!(
+                "line {}: expected 10 fields, found {}",
+                line_number,
+                fields.len()
+            )));
+        }
+        let (proposer, proposer_honest) = split_status(line_number, fields[1])?;
+        rows.push(CsvRow {
+            line: line_number,
+            block: parse_usize(line_number, fields[0])?,
+            proposer: parse_usize(line_number, proposer)?,
+            proposer_honest,
+            blob_id: parse_usize(line_number, fields[2])?,
+            votes: fields[3].trim_matches('"').to_string(),
+            honest_yes: parse_usize(line_number, fields[6])?,
+            malicious_no: parse_usize(line_number, fields[9])?,
+        });
+    }
+
+    Ok(rows)
+}
+
+// Rebuilds per-blob tallies from a `create_results_csv` file: honest Yes votes and
+// malicious No votes, buffered ones included, as the scenarios count them.
+pub fn read_blobs_csv<R: Read>(reader: R) -> io::Result<HashMap<usize, Blob>> {
+    let mut blobs: HashMap<usize, Blob> = HashMap::new();
+
+    for row in read_rows(reader)? {
+        // The CSV has no submission block; the first block that voted on the blob is
+        // the closest bound, since blobs may wait in the mempool before inclusion
+        let blob = blobs
+            .entry(row.blob_id)
+            .or_insert_with(|| Blob::new(row.blob_id, row.block, 0));
+        blob.submitted_at = blob.submitted_at.min(row.block);
+        blob.votes_honest += row.honest_yes;
+        blob.votes_malicious += row.malicious_no;
+    }
+
+    Ok(blobs)
+}
+
+// Rebuilds blocks from a `create_results_csv` file, together with the nodes the file
+// marks honest. Blocks without an open blob write no rows and are missing; the
+// included blob list and headers are only in the JSON output.
+pub fn read_blocks_csv<R: Read>(reader: R) -> io::Result<(Vec<Block>, HashSet<usize>)> {
+    let mut blocks: Vec<Block> = Vec::new();
+    let mut honest_nodes: HashSet<usize> = HashSet::new();
+
+    for row in read_rows(reader)? {
+        let line = row.line;
+        if row.proposer_honest {
+            honest_nodes.insert(row.proposer);
+        }
+
+        let mut committee = Vec::new();
+        let mut votes = Vec::new();
+        let mut buffered = Vec::new();
+        for entry in row.votes.split(';').filter(|entry| !entry.is_empty()) {
+            let (voter, rest) = entry
+                .split_once(':')
+                .ok_or_else(|| invalid_data(format!("line {}: '{}': no decision", line, entry)))?;
+            let (decision, honest) = split_status(line, rest)?;
+            let decision = match decision {
+                "yes" => Some(true),
+                "no" => Some(false),
+                "none" => None,
+                _ => {
+                    return Err(invalid_data(format!(
+                        "line {}: unknown decision '{}'",
+                        line, decision
+                    )))
+                }
+            };
+
+            let voter = match voter.split_once('@') {
+                Some((voter, cast_at)) => {
+                    let voter = parse_usize(line, voter)?;
+                    let decision = decision.ok_or_else(|| {
+                        invalid_data(format!("line {}: buffered vote without a decision", line))
+                    })?;
+                    buffered.push(BufferedVote {
+                        voter,
+                        cast_at: parse_usize(line, cast_at)?,
+                        decision,
+                    });
+                    voter
+                }
+                None => {
+                    let voter = parse_usize(line, voter)?;
+                    committee.push(voter);
+                    votes.push(decision);
+                    voter
+                }
+            };
+            if honest {
+                honest_nodes.insert(voter);
+            }
+        }
+
+        if blocks.last().is_none_or(|block| block.number != row.block) {
+            blocks.push(Block {
+                number: row.block,
+                proposer: row.proposer,
+                blobs: Vec::new(),
+                selected_nodes: committee.clone(),
+                votes: HashMap::new(),
+                buffered_votes: HashMap::new(),
+                header: None,
+            });
+        }
+        let block = blocks.last_mut().unwrap();
+        if block.proposer != row.proposer || block.selected_nodes != committee {
+            return Err(invalid_data(format!(
+                "line {}: proposer or committee differs from earlier rows of block {}",
+                line, row.block
+            )));
+        }
+        block.votes.insert(row.blob_id, votes);
+        if !buffered.is_empty() {
+            block.buffered_votes.insert(row.blob_id, buffered);
+        }
+    }
+
+    Ok((blocks, honest_nodes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Settings;
+    use crate::simulation::{Scenario, Simulation};
+    use crate::utils::csv_writer::create_results_csv;
+    use crate::utils::json_writer::create_results_json;
+    use crate::utils::{create_results_table, create_voting_summary_per_block};
+    use std::io::BufWriter;
+
+    // Censored votes make later blocks carry buffered votes
+    fn result() -> SimulationResult {
+        Simulation::builder()
+            .settings(Settings::for_tests())
+            .scenario(Scenario::VoteCensorship)
+            .run()
+            .unwrap()
+    }
+
+    #[test]
+    fn json_round_trip_reproduces_the_report() {
+        let result = result();
+        let mut writer = BufWriter::new(Vec::new());
+        create_results_json(&result, &mut writer).unwrap();
+        let json = writer.into_inner().unwrap();
+
+        let loaded = read_results_json(json.as_slice()).unwrap();
+        assert_eq!(loaded.honest_nodes, result.honest_nodes);
+        // Compared as JSON values, since maps iterate in arbitrary order
+        assert_eq!(
+            serde_json::to_value((&loaded.blocks, &loaded.blobs, &loaded.votes)).unwrap(),
+            serde_json::to_value((&result.blocks, &result.blobs, &result.votes)).unwrap()
+        );
+        assert_eq!(
+            create_results_table(&loaded.blocks, &loaded.honest_nodes).to_string(),
+            create_results_table(&result.blocks, &result.honest_nodes).to_string()
+        );
+        assert_eq!(
+            create_voting_summary_per_block(&loaded.blocks, &loaded.honest_nodes).to_string(),
+            create_voting_summary_per_block(&result.blocks, &result.honest_nodes).to_string()
+        );
+    }
+
+    #[test]
+    fn csv_round_trip_keeps_votes_and_tallies() {
+        let result = result();
+        assert!(result
+            .blocks
+            .iter()
+            .any(|block| !block.buffered_votes.is_empty()));
+        let mut writer = BufWriter::new(Vec::new());
+        create_results_csv(&result.blocks, &result.honest_nodes, &mut writer).unwrap();
+        let csv = writer.into_inner().unwrap();
+
+        let (blocks, honest_nodes) = read_blocks_csv(csv.as_slice()).unwrap();
+        assert_eq!(
+            create_results_table(&blocks, &honest_nodes).to_string(),
+            create_results_table(&result.blocks, &result.honest_nodes).to_string()
+        );
+        for blob in read_blobs_csv(csv.as_slice()).unwrap().values() {
+            let original = &result.blobs[&blob.id];
+            assert_eq!(
+                (blob.votes_honest, blob.votes_malicious),
+                (original.votes_honest, original.votes_malicious),
+                "blob {}",
+                blob.id
+            );
+        }
+    }
+}
@@ -0,0 +1,245 @@
+use crate::models::{Blob, RecoveryMetrics, RecoverySettings, SimulationResult};
+use prettytable::{format, Cell, Row, Table};
+use std::collections::{BTreeMap, HashMap};
+
+// Share of the votes included in each block that are honest Yes votes
+fn honest_share_series(result: &SimulationResult) -> BTreeMap<usize, f64> {
+    let mut counts: BTreeMap<usize, (usize, usize)> = BTreeMap::new();
+    for vote in result.votes.iter().filter(|vote| !vote.equivocation) {
+        if let Some(included_at) = vote.included_at {
+            let (honest_yes, total) = counts.entry(included_at).or_default();
+            *honest_yes += usize::from(vote.voter_honest && vote.decision);
+            *total += 1;
+        }
+    }
+    counts
+        .into_iter()
+        .map(|(block, (honest_yes, total))| (block, honest_yes as f64 / total as f64))
+        .collect()
+}
+
+// Whether each confirmed blob ended up accepted, i.e. had more Yes than No votes
+// included by the time it confirmed
+fn verdicts(result: &SimulationResult) -> HashMap<usize, bool> {
+    let mut tallies: HashMap<usize, (usize, usize)> = HashMap::new();
+    for vote in result.votes.iter().filter(|vote| !vote.equivocation) {
+        let blob = &result.blobs[&vote.blob_id];
+        let (Some(included_at), Some(confirmed_at)) = (vote.included_at, blob.confirmed_at) else {
+            continue;
+        };
+        if included_at <= confirmed_at {
+            let (yes, no) = tallies.entry(vote.blob_id).or_default();
+            *yes += usize::from(vote.decision);
+            *no += usize::from(!vote.decision);
+        }
+    }
+    result
+        .blobs
+        .values()
+        .filter(|blob| blob.confirmed_at.is_some())
+        .map(|blob| {
+            let (yes, no) = tallies.get(&blob.id).copied().unwrap_or_default();
+            (blob.id, yes > no)
+        })
+        .collect()
+}
+
+// A blob is available unless chunks were withheld and the network could not rebuild it
+fn available(blob: &Blob) -> bool {
+    blob.withheld_chunks.is_empty() || blob.recoverable == Some(true)
+}
+
+fn mean(values: impl Iterator<Item = f64>) -> Option<f64> {
+    let (sum, count) = values.fold((0.0, 0), |(sum, count), value| (sum + value, count + 1));
+    (count > 0).then(|| sum / count as f64)
+}
+
+// Blocks after `attack_end` until the rolling mean of `series` first stays within the
+// band around `baseline`; 0 means it was back right away
+fn recovery_time(
+    series: &BTreeMap<usize, f64>,
+    attack_end: usize,
+    total_blocks: usize,
+    baseline: f64,
+    settings: &RecoverySettings,
+) -> Option<usize> {
+    let window = settings.window.max(1);
+    (attack_end + 1..=total_blocks)
+        .take_while(|start| start + window - 1 <= total_blocks)
+        .find(|&start| {
+            mean(series.range(start..start + window).map(|(_, &value)| value))
+                .is_some_and(|value| (value - baseline).abs() <= settings.band)
+        })
+        .map(|start| start - attack_end - 1)
+}
+
+// Recovery after the run's attack window; None when the run has no attack window
+pub fn recovery_metrics(result: &SimulationResult) -> Option<RecoveryMetrics> {
+    let (attack_start, attack_end) = result.attack_window()?;
+    let settings = &result.settings.recovery;
+    let total_blocks = result.blocks.len();
+
+    let honest_share = honest_share_series(result);
+    let verdicts = verdicts(result);
+    let mut acceptance: BTreeMap<usize, (usize, usize)> = BTreeMap::new();
+    for (blob_id, &accepted) in &verdicts {
+        let confirmed_at = result.blobs[blob_id].confirmed_at.unwrap();
+        let (accepted_count, total) = acceptance.entry(confirmed_at).or_default();
+        *accepted_count += usize::from(accepted);
+        *total += 1;
+    }
+    let acceptance: BTreeMap<usize, f64> = acceptance
+        .into_iter()
+        .map(|(block, (accepted, total))| (block, accepted as f64 / total as f64))
+        .collect();
+
+    let baseline_honest_share = mean(honest_share.range(..attack_start).map(|(_, &v)| v));
+    let baseline_acceptance = mean(acceptance.range(..attack_start).map(|(_, &v)| v));
+    let recovery = |series: &BTreeMap<usize, f64>, baseline: Option<f64>| {
+        baseline.and_then(|baseline| {
+            recovery_time(series, attack_end, total_blocks, baseline, settings)
+        })
+    };
+
+    let wrong: Vec<&Blob> = verdicts
+        .iter()
+        .filter(|(blob_id, &accepted)| accepted != available(&result.blobs[blob_id]))
+        .map(|(blob_id, _)| &result.blobs[blob_id])
+        .collect();
+    let unconfirmed_at_end = |blob: &Blob| {
+        blob.confirmed_at
+            .is_none_or(|confirmed_at| confirmed_at > attack_end)
+    };
+
+    Some(RecoveryMetrics {
+        attack_start,
+        attack_end,
+        baseline_honest_share,
+        baseline_acceptance,
+        honest_share_recovery: recovery(&honest_share, baseline_honest_share),
+        acceptance_recovery: recovery(&acceptance, baseline_acceptance),
+        wrongly_decided: wrong.len(),
+        wrongly_decided_in_attack: wrong
+            .iter()
+            .filter(|blob| blob.submitted_at <= attack_end)
+            .count(),
+        backlog_at_attack_end: result
+            .blobs
+            .values()
+            .filter(|blob| blob.submitted_at <= attack_end && unconfirmed_at_end(blob))
+            .count(),
+        pending_votes_at_attack_end: result
+            .votes
+            .iter()
+            .filter(|vote| {
+                !vote.equivocation
+                    && vote.cast_at <= attack_end
+                    && vote
+                        .included_at
+                        .is_none_or(|included_at| included_at > attack_end)
+                    && unconfirmed_at_end(&result.blobs[&vote.blob_id])
+            })
+            .count(),
+    })
+}
+
+// Recovery after the attack window, against the pre-attack baseline
+pub fn create_recovery_table(metrics: Option<&RecoveryMetrics>) -> Table {
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_BOX_CHARS);
+    table.add_row(Row::new(vec![Cell::new("Recovery"), Cell::new("Value")]));
+
+    let Some(metrics) = metrics else {
+        table.add_row(Row::new(vec![
+            Cell::new("Attack window"),
+            Cell::new("none"),
+        ]));
+        return table;
+    };
+
+    let share = |value: Option<f64>| match value {
+        Some(value) => format!("{:.2}%", value * 100.0),
+        None => "no pre-attack blocks".to_string(),
+    };
+    let recovered = |baseline: Option<f64>, blocks: Option<usize>| match (baseline, blocks) {
+        (None, _) => "no baseline".to_string(),
+        (Some(_), Some(blocks)) => format!("{} block(s)", blocks),
+        (Some(_), None) => "not recovered".to_string(),
+    };
+
+    for (name, value) in [
+        (
+            "Attack window",
+            format!("{}-{}", metrics.attack_start, metrics.attack_end),
+        ),
+        (
+            "Baseline honest vote share",
+            share(metrics.baseline_honest_share),
+        ),
+        (
+            "Honest vote share back in band after",
+            recovered(metrics.baseline_honest_share, metrics.honest_share_recovery),
+        ),
+        (
+            "Baseline acceptance rate",
+            share(metrics.baseline_acceptance),
+        ),
+        (
+            "Acceptance rate back in band after",
+            recovered(metrics.baseline_acceptance, metrics.acceptance_recovery),
+        ),
+        (
+            "Wrongly decided blobs",
+            format!(
+                "{} ({} submitted by the attack's end)",
+                metrics.wrongly_decided, metrics.wrongly_decided_in_attack
+            ),
+        ),
+        (
+            "Unconfirmed backlog at attack end",
+            format!("{} blobs", metrics.backlog_at_attack_end),
+        ),
+        (
+            "Censored votes pending at attack end",
+            metrics.pending_votes_at_attack_end.to_string(),
+        ),
+    ] {
+        table.add_row(Row::new(vec![Cell::new(name), Cell::new(&value)]));
+    }
+
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Attack ends at block 10 of 20; blocks 1-10 and those after `tail` hold 1.0
+    fn series(tail: &[f64]) -> BTreeMap<usize, f64> {
+        (1..=20usize)
+            .map(|block| {
+                let after = block.checked_sub(11).and_then(|i| tail.get(i));
+                (block, after.copied().unwrap_or(1.0))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn recovery_time_waits_for_the_rolling_mean() {
+        let settings = RecoverySettings {
+            band: 0.05,
+            window: 3,
+        };
+
+        // Windows starting at 11 and 12 average 0.77 and 0.93; the one at 13 is back
+        let recovering = series(&[0.5, 0.8]);
+        assert_eq!(recovery_time(&recovering, 10, 20, 1.0, &settings), Some(2));
+        assert_eq!(recovery_time(&series(&[]), 10, 20, 1.0, &settings), Some(0));
+        assert_eq!(
+            recovery_time(&series(&[0.5; 10]), 10, 20, 1.0, &settings),
+            None
+        );
+        // No full window fits after block 19
+        assert_eq!(recovery_time(&series(&[]), 19, 20, 1.0, &settings), None);
+    }
+}
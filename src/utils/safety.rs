@@ -0,0 +1,282 @@
+use crate::models::{ForkMetrics, SimulationResult};
+use prettytable::{format, Cell, Row, Table};
+
+// Depths tabulated for a single run, along with the configured confirmation depth
+const DEPTHS: [usize; 13] = [1, 2, 3, 4, 5, 6, 8, 10, 15, 20, 30, 50, 100];
+
+// Probability that an attacker with share `q` of block production ever overtakes a
+// block buried under `z` further blocks, from section 11 of the Bitcoin paper
+pub fn nakamoto_reversal_probability(q: f64, z: usize) -> f64 {
+    let p = 1.0 - q;
+    if q >= p {
+        return 1.0;
+    }
+    let lambda = z as f64 * q / p;
+    let mut poisson = (-lambda).exp();
+    let mut probability = 1.0;
+    for k in 0..=z {
+        if k > 0 {
+            poisson *= lambda / k as f64;
+        }
+        probability -= poisson * (1.0 - (q / p).powi((z - k) as i32));
+    }
+    probability.max(0.0)
+}
+
+// Closed-form reversal probability for a blob whose including block is `depth` deep
+pub fn closed_form_reversal(q: f64, depth: usize) -> f64 {
+    nakamoto_reversal_probability(q, depth.saturating_sub(1))
+}
+
+// Blocks of a run that were `depth` deep on the canonical chain at some point,
+// and how many of those a reorg later removed
+#[derive(Debug, Clone, Copy)]
+pub struct DepthSafety {
+    pub depth: usize,
+    pub reached: usize,
+    pub reverted: usize,
+}
+
+impl DepthSafety {
+    pub fn probability(&self) -> f64 {
+        if self.reached == 0 {
+            return 0.0;
+        }
+        self.reverted as f64 / self.reached as f64
+    }
+}
+
+// A reorg of depth r removes one block at each depth up to r, and every block left on
+// the final chain reached its final depth
+pub fn depth_safety(metrics: &ForkMetrics, chain_length: usize, depth: usize) -> DepthSafety {
+    let reverted = metrics
+        .reorg_depths
+        .iter()
+        .filter(|&&reorg| reorg >= depth)
+        .count();
+    let kept = (chain_length + 1).saturating_sub(depth);
+    DepthSafety {
+        depth,
+        reached: kept + reverted,
+        reverted,
+    }
+}
+
+// Canonical blocks a run needs so that every depth up to `max_depth` is reached by
+// the `1 / target` blocks `simulated_depth` requires
+pub fn blocks_needed(target: f64, max_depth: usize) -> usize {
+    (1.0 / target).ceil() as usize + max_depth.saturating_sub(1)
+}
+
+// Smallest depth whose simulated reversal rate meets `target`, counting only depths
+// reached by enough blocks to resolve it
+pub fn simulated_depth(
+    metrics: &ForkMetrics,
+    chain_length: usize,
+    target: f64,
+    max_depth: usize,
+) -> Option<usize> {
+    let needed = (1.0 / target).ceil() as usize;
+    (1..=max_depth).find(|&depth| {
+        let safety = depth_safety(metrics, chain_length, depth);
+        safety.reached >= needed && safety.probability() <= target
+    })
+}
+
+pub fn closed_form_depth(q: f64, target: f64, max_depth: usize) -> Option<usize> {
+    (1..=max_depth).find(|&depth| closed_form_reversal(q, depth) <= target)
+}
+
+fn depth_cell(depth: Option<usize>, max_depth: usize) -> String {
+    match depth {
+        Some(depth) => depth.to_string(),
+        None => format!("> {}", max_depth),
+    }
+}
+
+// A chain too short to reach the depth that meets the target says so instead of
+// reporting the depth as beyond `max_depth`
+fn simulated_depth_cell(
+    metrics: &ForkMetrics,
+    chain_length: usize,
+    target: f64,
+    max_depth: usize,
+) -> String {
+    let needed = blocks_needed(target, max_depth);
+    match simulated_depth(metrics, chain_length, target, max_depth) {
+        None if chain_length < needed => {
+            format!("unresolved, {} of {} blocks", chain_length, needed)
+        }
+        depth => depth_cell(depth, max_depth),
+    }
+}
+
+// Simulated and closed-form reversal probability by confirmation depth for one run
+pub fn create_safety_table(result: &SimulationResult) -> Table {
+    let metrics = result.fork.clone().unwrap_or_default();
+    let settings = &result.settings;
+    let chain_length = result.blocks.len();
+    let q = metrics.malicious_power;
+
+    let mut depths: Vec<usize> = DEPTHS
+        .iter()
+        .copied()
+        .chain([settings.confirmation_depth])
+        .filter(|&depth| depth >= 1 && depth <= settings.safety.max_depth)
+        .collect();
+    depths.sort_unstable();
+    depths.dedup();
+
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_BOX_CHARS);
+    table.add_row(Row::new(vec![
+        Cell::new("Depth"),
+        Cell::new("Blocks Reaching Depth"),
+        Cell::new("Reverted"),
+        Cell::new("Simulated P(revert)"),
+        Cell::new("Closed-form P(revert)"),
+    ]));
+    for depth in depths {
+        let safety = depth_safety(&metrics, chain_length, depth);
+        let label = if depth == settings.confirmation_depth {
+            format!("{} (configured)", depth)
+        } else {
+            depth.to_string()
+        };
+        table.add_row(Row::new(vec![
+            Cell::new(&label),
+            Cell::new(&safety.reached.to_string()),
+            Cell::new(&safety.reverted.to_string()),
+            Cell::new(&format!("{:.6}", safety.probability())),
+            Cell::new(&format!("{:.6}", closed_form_reversal(q, depth))),
+        ]));
+    }
+
+    let target = settings.safety.target;
+    let max_depth = settings.safety.max_depth;
+    table.add_row(Row::new(vec![
+        Cell::new(&format!("Minimal depth for P <= {}", target)),
+        Cell::new(""),
+        Cell::new(""),
+        Cell::new(&simulated_depth_cell(
+            &metrics,
+            chain_length,
+            target,
+            max_depth,
+        )),
+        Cell::new(&depth_cell(
+            closed_form_depth(q, target, max_depth),
+            max_depth,
+        )),
+    ]));
+
+    table
+}
+
+// Reversal probability at the configured depth and the minimal safe depth, one
+// selfish mining run per adversary power level
+pub fn create_safety_sweep_table(results: &[SimulationResult]) -> Table {
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_BOX_CHARS);
+    let Some(first) = results.first() else {
+        return table;
+    };
+    let settings = &first.settings;
+    let (depth, target, max_depth) = (
+        settings.confirmation_depth,
+        settings.safety.target,
+        settings.safety.max_depth,
+    );
+
+    table.add_row(Row::new(vec![
+        Cell::new("Adversary Power"),
+        Cell::new("Reorgs"),
+        Cell::new(&format!("Simulated P(revert) at {}", depth)),
+        Cell::new(&format!("Closed-form P(revert) at {}", depth)),
+        Cell::new(&format!("Simulated Depth for P <= {}", target)),
+        Cell::new(&format!("Closed-form Depth for P <= {}", target)),
+    ]));
+    for result in results {
+        let metrics = result.fork.clone().unwrap_or_default();
+        let chain_length = result.blocks.len();
+        let q = metrics.malicious_power;
+        table.add_row(Row::new(vec![
+            Cell::new(&format!("{:.0}%", q * 100.0)),
+            Cell::new(&metrics.reorg_depths.len().to_string()),
+            Cell::new(&format!(
+                "{:.6}",
+                depth_safety(&metrics, chain_length, depth).probability()
+            )),
+            Cell::new(&format!("{:.6}", closed_form_reversal(q, depth))),
+            Cell::new(&simulated_depth_cell(
+                &metrics,
+                chain_length,
+                target,
+                max_depth,
+            )),
+            Cell::new(&depth_cell(
+                closed_form_depth(q, target, max_depth),
+                max_depth,
+            )),
+        ]));
+    }
+
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-7,
+            "{} differs from {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn reversal_probability_matches_bitcoin_paper() {
+        // Values from the table in section 11 of the Bitcoin paper
+        assert_close(nakamoto_reversal_probability(0.1, 0), 1.0);
+        assert_close(nakamoto_reversal_probability(0.1, 1), 0.2045873);
+        assert_close(nakamoto_reversal_probability(0.1, 2), 0.0509779);
+        assert_close(nakamoto_reversal_probability(0.1, 5), 0.0009137);
+        assert_close(nakamoto_reversal_probability(0.1, 10), 0.0000012);
+        assert_close(nakamoto_reversal_probability(0.3, 5), 0.1773523);
+        assert_close(nakamoto_reversal_probability(0.3, 10), 0.0416605);
+        assert_eq!(nakamoto_reversal_probability(0.5, 100), 1.0);
+    }
+
+    #[test]
+    fn closed_form_depth_counts_the_including_block() {
+        assert_close(closed_form_reversal(0.1, 6), 0.0009137);
+        // Five blocks on top fall below 0.1% but four (0.0034552) do not
+        assert_eq!(closed_form_depth(0.1, 0.001, 100), Some(6));
+        assert_eq!(closed_form_depth(0.5, 0.001, 100), None);
+    }
+
+    #[test]
+    fn simulated_depth_needs_enough_blocks() {
+        let metrics = ForkMetrics {
+            reorg_depths: vec![1, 1, 2],
+            ..Default::default()
+        };
+
+        let safety = depth_safety(&metrics, 200, 1);
+        assert_eq!((safety.reached, safety.reverted), (203, 3));
+        let safety = depth_safety(&metrics, 200, 2);
+        assert_eq!((safety.reached, safety.reverted), (200, 1));
+        assert_eq!(simulated_depth(&metrics, 200, 0.01, 10), Some(2));
+
+        assert_eq!(blocks_needed(0.01, 10), 109);
+        assert_eq!(simulated_depth(&metrics, 50, 0.01, 10), None);
+        assert_eq!(
+            simulated_depth_cell(&metrics, 50, 0.01, 10),
+            "unresolved, 50 of 109 blocks"
+        );
+        assert_eq!(simulated_depth_cell(&metrics, 200, 0.01, 10), "2");
+    }
+}
@@ -0,0 +1,70 @@
+use crate::models::{Blob, Block, PhaseRecord, VoteRecord};
+use prettytable::{format, Cell, Row, Table};
+use std::collections::{HashMap, HashSet};
+
+// What happened in each phase of the attack schedule
+pub fn create_phase_table(
+    phases: &[PhaseRecord],
+    blocks: &[Block],
+    blobs: &HashMap<usize, Blob>,
+    votes: &[VoteRecord],
+    honest_nodes: &HashSet<usize>,
+) -> Table {
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_BOX_CHARS);
+    table.add_row(Row::new(vec![
+        Cell::new("Phase"),
+        Cell::new("Blocks"),
+        Cell::new("Behaviors"),
+        Cell::new("Malicious Proposers"),
+        Cell::new("Honest Yes Share"),
+        Cell::new("Censored Votes"),
+        Cell::new("Blobs Confirmed"),
+    ]));
+
+    for phase in phases {
+        let in_phase = |block: usize| (phase.start_block..=phase.end_block).contains(&block);
+        let behaviors: Vec<&str> = phase.behaviors.iter().map(|b| b.name()).collect();
+        let behaviors = if behaviors.is_empty() {
+            "none".to_string()
+        } else {
+            behaviors.join(", ")
+        };
+        let malicious_proposers = blocks
+            .iter()
+            .filter(|block| in_phase(block.number) && !honest_nodes.contains(&block.proposer))
+            .count();
+        let honest_votes: Vec<&VoteRecord> = votes
+            .iter()
+            .filter(|vote| vote.voter_honest && in_phase(vote.cast_at))
+            .collect();
+        let honest_yes = honest_votes.iter().filter(|vote| vote.decision).count();
+        let censored = honest_votes
+            .iter()
+            .filter(|vote| vote.included_at != Some(vote.cast_at))
+            .count();
+        let confirmed = blobs
+            .values()
+            .filter(|blob| blob.confirmed_at.is_some_and(in_phase))
+            .count();
+
+        table.add_row(Row::new(vec![
+            Cell::new(&phase.name),
+            Cell::new(&format!("{}-{}", phase.start_block, phase.end_block)),
+            Cell::new(&behaviors),
+            Cell::new(&malicious_proposers.to_string()),
+            Cell::new(&if honest_votes.is_empty() {
+                "-".to_string()
+            } else {
+                format!(
+                    "{:.2}%",
+                    honest_yes as f64 / honest_votes.len() as f64 * 100.0
+                )
+            }),
+            Cell::new(&censored.to_string()),
+            Cell::new(&confirmed.to_string()),
+        ]));
+    }
+
+    table
+}
@@ -0,0 +1,116 @@
+use crate::models::{SelectiveMetrics, SplitVote};
+use prettytable::{format, Cell, Row, Table};
+
+fn yes_weight_share(split: &SplitVote) -> f64 {
+    let total = split.yes_weight + split.no_weight;
+    if total == 0.0 {
+        0.0
+    } else {
+        split.yes_weight / total
+    }
+}
+
+fn mean(values: impl Iterator<Item = f64>) -> f64 {
+    let (sum, count) = values.fold((0.0, 0), |(sum, count), value| (sum + value, count + 1));
+    if count == 0 {
+        0.0
+    } else {
+        sum / count as f64
+    }
+}
+
+// How split honest votes on selectively served blobs change their outcome
+pub fn create_selective_table(metrics: &SelectiveMetrics) -> Table {
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_BOX_CHARS);
+
+    table.add_row(Row::new(vec![
+        Cell::new(&format!(
+            "Blobs ({}, {:.0}% served)",
+            metrics.targeting,
+            metrics.serve_fraction * 100.0
+        )),
+        Cell::new("Blobs"),
+        Cell::new("Confirmed"),
+        Cell::new("Split"),
+        Cell::new("Accepted"),
+        Cell::new("Rejected"),
+        Cell::new("Mean Honest Yes Share"),
+        Cell::new("Mean Yes Weight Share"),
+    ]));
+
+    for (name, attacked) in [("Not attacked", false), ("Attacked", true)] {
+        let group: Vec<&SplitVote> = metrics
+            .votes
+            .iter()
+            .filter(|split| split.attacked == attacked)
+            .collect();
+        let confirmed: Vec<&SplitVote> = group
+            .iter()
+            .copied()
+            .filter(|split| split.confirmed_at.is_some())
+            .collect();
+        let split = group
+            .iter()
+            .filter(|split| split.honest_yes > 0 && split.honest_no > 0)
+            .count();
+        let accepted = confirmed.iter().filter(|split| split.accepted()).count();
+        let honest_yes_share = mean(group.iter().filter_map(|split| {
+            let honest = split.honest_yes + split.honest_no;
+            (honest > 0).then(|| split.honest_yes as f64 / honest as f64)
+        }));
+
+        table.add_row(Row::new(vec![
+            Cell::new(name),
+            Cell::new(&group.len().to_string()),
+            Cell::new(&confirmed.len().to_string()),
+            Cell::new(&split.to_string()),
+            Cell::new(&accepted.to_string()),
+            Cell::new(&(confirmed.len() - accepted).to_string()),
+            Cell::new(&format!("{:.2}%", honest_yes_share * 100.0)),
+            Cell::new(&format!(
+                "{:.2}%",
+                mean(group.iter().map(|split| yes_weight_share(split))) * 100.0
+            )),
+        ]));
+    }
+
+    table
+}
+
+// Per-blob vote split for every attacked blob
+pub fn create_split_vote_table(metrics: &SelectiveMetrics) -> Table {
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_BOX_CHARS);
+
+    table.add_row(Row::new(vec![
+        Cell::new("Blob ID"),
+        Cell::new("Honest Yes"),
+        Cell::new("Honest No"),
+        Cell::new("Yes Weight Share"),
+        Cell::new("Confirmed At"),
+        Cell::new("Outcome"),
+    ]));
+
+    for split in metrics.votes.iter().filter(|split| split.attacked) {
+        let outcome = match split.confirmed_at {
+            None => "pending",
+            Some(_) if split.accepted() => "accepted",
+            Some(_) => "rejected",
+        };
+        table.add_row(Row::new(vec![
+            Cell::new(&split.blob_id.to_string()),
+            Cell::new(&split.honest_yes.to_string()),
+            Cell::new(&split.honest_no.to_string()),
+            Cell::new(&format!("{:.2}%", yes_weight_share(split) * 100.0)),
+            Cell::new(
+                &split
+                    .confirmed_at
+                    .map_or("-".to_string(), |block| block.to_string()),
+            ),
+            Cell::new(outcome),
+        ]));
+    }
+
+    table
+}
@@ -0,0 +1,87 @@
+use crate::models::EquivocationMetrics;
+use prettytable::{format, Cell, Row, Table};
+
+// Rows in the power table besides the first and last block
+const POWER_SAMPLES: usize = 10;
+
+// Double votes, how many were caught, and what slashing took from the adversary
+pub fn create_slashing_table(metrics: &EquivocationMetrics) -> Table {
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_BOX_CHARS);
+    table.add_row(Row::new(vec![
+        Cell::new(&format!("Equivocation ({} penalty)", metrics.penalty)),
+        Cell::new("Value"),
+    ]));
+
+    let final_power = metrics
+        .effective_power
+        .last()
+        .copied()
+        .unwrap_or(metrics.initial_power);
+    let block = |block: Option<usize>| match block {
+        Some(block) => format!("block {}", block),
+        None => "never".to_string(),
+    };
+    for (name, value) in [
+        ("Equivocations", metrics.equivocations.to_string()),
+        (
+            "Detected on chain",
+            format!(
+                "{} ({} hidden by malicious proposers)",
+                metrics.detected, metrics.hidden
+            ),
+        ),
+        ("Slashed nodes", metrics.slashed_nodes.to_string()),
+        (
+            "Effective adversary power (start / end)",
+            format!(
+                "{:.2}% / {:.2}%",
+                metrics.initial_power * 100.0,
+                final_power * 100.0
+            ),
+        ),
+        ("Power halved by", block(metrics.power_below(0.5))),
+        ("Power below a tenth by", block(metrics.power_below(0.1))),
+        (
+            "Adversary rewards (kept / lost)",
+            format!(
+                "{:.2} / {:.2}",
+                metrics.adversary_rewards, metrics.rewards_lost
+            ),
+        ),
+    ] {
+        table.add_row(Row::new(vec![Cell::new(name), Cell::new(&value)]));
+    }
+
+    table
+}
+
+// Effective adversary power and slashed nodes at evenly spaced blocks
+pub fn create_power_table(metrics: &EquivocationMetrics) -> Table {
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_BOX_CHARS);
+    table.add_row(Row::new(vec![
+        Cell::new("Block"),
+        Cell::new("Slashed Nodes"),
+        Cell::new("Effective Adversary Power"),
+    ]));
+
+    let blocks = metrics.effective_power.len();
+    let mut samples: Vec<usize> = (0..=POWER_SAMPLES)
+        .map(|sample| (blocks * sample / POWER_SAMPLES).max(1))
+        .filter(|&block| block <= blocks)
+        .collect();
+    samples.dedup();
+    for block in samples {
+        table.add_row(Row::new(vec![
+            Cell::new(&block.to_string()),
+            Cell::new(&metrics.slashed_by_block[block - 1].to_string()),
+            Cell::new(&format!(
+                "{:.2}%",
+                metrics.effective_power[block - 1] * 100.0
+            )),
+        ]));
+    }
+
+    table
+}
@@ -0,0 +1,154 @@
+use crate::models::{Blob, SimulationResult};
+use prettytable::{format, Cell, Row, Table};
+
+// What junk blobs cost honest users and committees in one run
+pub struct SpamImpact {
+    pub honest_submitted: usize,
+    pub honest_confirmed: usize,
+    // Mean blocks from submission to confirmation of confirmed honest blobs
+    pub mean_delay: f64,
+    // Mean blocks honest blobs waited in the mempool before inclusion
+    pub mean_wait: f64,
+    pub junk_included: usize,
+    pub junk_votes: usize,
+    pub total_votes: usize,
+}
+
+impl SpamImpact {
+    pub fn from_result(result: &SimulationResult) -> Self {
+        let honest: Vec<&Blob> = result
+            .blobs
+            .values()
+            .filter(|blob| blob.proposer_status == "honest")
+            .collect();
+        let delays: Vec<usize> = honest
+            .iter()
+            .filter_map(|blob| blob.confirmation_latency())
+            .collect();
+        let waits: Vec<usize> = honest
+            .iter()
+            .filter_map(|blob| blob.included_at.map(|at| at - blob.submitted_at))
+            .collect();
+        let junk_votes = result
+            .votes
+            .iter()
+            .filter(|vote| result.blobs[&vote.blob_id].proposer_status != "honest")
+            .count();
+
+        Self {
+            honest_submitted: honest.len(),
+            honest_confirmed: delays.len(),
+            mean_delay: mean(&delays),
+            mean_wait: mean(&waits),
+            junk_included: result
+                .blobs
+                .values()
+                .filter(|blob| blob.proposer_status != "honest" && blob.included_at.is_some())
+                .count(),
+            junk_votes,
+            total_votes: result.votes.len(),
+        }
+    }
+
+    // Share of all votes cast on junk blobs
+    pub fn vote_overhead(&self) -> f64 {
+        if self.total_votes == 0 {
+            0.0
+        } else {
+            self.junk_votes as f64 / self.total_votes as f64
+        }
+    }
+}
+
+fn mean(values: &[usize]) -> f64 {
+    if values.is_empty() {
+        0.0
+    } else {
+        values.iter().sum::<usize>() as f64 / values.len() as f64
+    }
+}
+
+// Junk submissions, what the countermeasures stopped and the cost to honest blobs
+pub fn create_spam_table(result: &SimulationResult) -> Table {
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_BOX_CHARS);
+    table.add_row(Row::new(vec![Cell::new("Spam"), Cell::new("Value")]));
+
+    let metrics = result.spam.clone().unwrap_or_default();
+    let impact = SpamImpact::from_result(result);
+    for (name, value) in [
+        ("Spam rate", format!("{}", result.settings.spam.rate)),
+        ("Junk blobs submitted", metrics.submitted.to_string()),
+        (
+            "Refused (budget / rate limit)",
+            format!(
+                "{} / {}",
+                metrics.refused_budget, metrics.refused_rate_limit
+            ),
+        ),
+        ("Unavailable junk blobs", metrics.unavailable.to_string()),
+        ("Fees paid", format!("{:.2}", metrics.fees_paid)),
+        ("Junk blobs included", impact.junk_included.to_string()),
+        (
+            "Honest blobs confirmed",
+            format!("{} / {}", impact.honest_confirmed, impact.honest_submitted),
+        ),
+        (
+            "Mean honest confirmation delay",
+            format!("{:.2}", impact.mean_delay),
+        ),
+        (
+            "Mean honest mempool wait",
+            format!("{:.2}", impact.mean_wait),
+        ),
+        (
+            "Votes on junk blobs",
+            format!(
+                "{} ({:.2}%)",
+                impact.junk_votes,
+                impact.vote_overhead() * 100.0
+            ),
+        ),
+        (
+            "Junk vote bytes",
+            (impact.junk_votes * result.settings.bandwidth.vote_bytes).to_string(),
+        ),
+    ] {
+        table.add_row(Row::new(vec![Cell::new(name), Cell::new(&value)]));
+    }
+
+    table
+}
+
+// Honest confirmation delay and vote overhead at each swept spam rate
+pub fn create_spam_sweep_table(results: &[SimulationResult]) -> Table {
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_BOX_CHARS);
+    table.add_row(Row::new(vec![
+        Cell::new("Spam Rate"),
+        Cell::new("Junk Submitted"),
+        Cell::new("Junk Refused"),
+        Cell::new("Honest Confirmed"),
+        Cell::new("Mean Honest Confirmation Delay"),
+        Cell::new("Mean Honest Mempool Wait"),
+        Cell::new("Votes on Junk"),
+    ]));
+    for result in results {
+        let metrics = result.spam.clone().unwrap_or_default();
+        let impact = SpamImpact::from_result(result);
+        table.add_row(Row::new(vec![
+            Cell::new(&format!("{}", result.settings.spam.rate)),
+            Cell::new(&metrics.submitted.to_string()),
+            Cell::new(&(metrics.refused_budget + metrics.refused_rate_limit).to_string()),
+            Cell::new(&format!(
+                "{} / {}",
+                impact.honest_confirmed, impact.honest_submitted
+            )),
+            Cell::new(&format!("{:.2}", impact.mean_delay)),
+            Cell::new(&format!("{:.2}", impact.mean_wait)),
+            Cell::new(&format!("{:.2}%", impact.vote_overhead() * 100.0)),
+        ]));
+    }
+
+    table
+}
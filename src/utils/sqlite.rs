@@ -0,0 +1,328 @@
+use crate::models::SimulationResult;
+use crate::ENGINE_VERSION;
+use rusqlite::{params, Connection};
+use std::path::Path;
+
+// Every table is keyed by `run_id`, so one database can hold many runs
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS runs (
+    run_id TEXT PRIMARY KEY,
+    scenario TEXT NOT NULL,
+    seed INTEGER,
+    engine_version INTEGER NOT NULL,
+    settings TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS nodes (
+    run_id TEXT NOT NULL REFERENCES runs(run_id) ON DELETE CASCADE,
+    node_id INTEGER NOT NULL,
+    is_honest INTEGER NOT NULL,
+    PRIMARY KEY (run_id, node_id)
+);
+
+CREATE TABLE IF NOT EXISTS blocks (
+    run_id TEXT NOT NULL REFERENCES runs(run_id) ON DELETE CASCADE,
+    number INTEGER NOT NULL,
+    proposer INTEGER NOT NULL,
+    parent_hash TEXT,
+    blob_root TEXT,
+    vote_root TEXT,
+    hash TEXT,
+    PRIMARY KEY (run_id, number),
+    FOREIGN KEY (run_id, proposer) REFERENCES nodes(run_id, node_id)
+);
+CREATE INDEX IF NOT EXISTS blocks_proposer ON blocks(run_id, proposer);
+
+CREATE TABLE IF NOT EXISTS blobs (
+    run_id TEXT NOT NULL REFERENCES runs(run_id) ON DELETE CASCADE,
+    blob_id INTEGER NOT NULL,
+    votes_honest INTEGER NOT NULL,
+    votes_malicious INTEGER NOT NULL,
+    is_confirmed INTEGER NOT NULL,
+    proposer_status TEXT NOT NULL,
+    PRIMARY KEY (run_id, blob_id)
+);
+
+CREATE TABLE IF NOT EXISTS committees (
+    run_id TEXT NOT NULL,
+    block_number INTEGER NOT NULL,
+    position INTEGER NOT NULL,
+    node_id INTEGER NOT NULL,
+    PRIMARY KEY (run_id, block_number, position),
+    FOREIGN KEY (run_id, block_number) REFERENCES blocks(run_id, number) ON DELETE CASCADE,
+    FOREIGN KEY (run_id, node_id) REFERENCES nodes(run_id, node_id)
+);
+CREATE INDEX IF NOT EXISTS committees_node ON committees(run_id, node_id);
+
+-- decision: 1 = yes, 0 = no, NULL = no vote recorded (e.g. censored)
+CREATE TABLE IF NOT EXISTS votes (
+    run_id TEXT NOT NULL,
+    block_number INTEGER NOT NULL,
+    blob_id INTEGER NOT NULL,
+    position INTEGER NOT NULL,
+    node_id INTEGER NOT NULL,
+    decision INTEGER,
+    PRIMARY KEY (run_id, block_number, blob_id, position),
+    FOREIGN KEY (run_id, block_number, position)
+        REFERENCES committees(run_id, block_number, position) ON DELETE CASCADE,
+    FOREIGN KEY (run_id, blob_id) REFERENCES blobs(run_id, blob_id),
+    FOREIGN KEY (run_id, node_id) REFERENCES nodes(run_id, node_id)
+);
+CREATE INDEX IF NOT EXISTS votes_node ON votes(run_id, node_id);
+CREATE INDEX IF NOT EXISTS votes_blob ON votes(run_id, blob_id);
+
+-- Votes cast in an earlier block (cast_at), in their order within the buffer
+CREATE TABLE IF NOT EXISTS buffered_votes (
+    run_id TEXT NOT NULL,
+    block_number INTEGER NOT NULL,
+    blob_id INTEGER NOT NULL,
+    position INTEGER NOT NULL,
+    node_id INTEGER NOT NULL,
+    cast_at INTEGER NOT NULL,
+    decision INTEGER NOT NULL,
+    PRIMARY KEY (run_id, block_number, blob_id, position),
+    FOREIGN KEY (run_id, block_number) REFERENCES blocks(run_id, number) ON DELETE CASCADE,
+    FOREIGN KEY (run_id, blob_id) REFERENCES blobs(run_id, blob_id),
+    FOREIGN KEY (run_id, node_id) REFERENCES nodes(run_id, node_id)
+);
+CREATE INDEX IF NOT EXISTS buffered_votes_blob ON buffered_votes(run_id, blob_id);
+CREATE INDEX IF NOT EXISTS buffered_votes_node ON buffered_votes(run_id, node_id);
+
+-- Every vote from cast to inclusion; included_at is NULL if it never landed
+CREATE TABLE IF NOT EXISTS vote_records (
+    run_id TEXT NOT NULL REFERENCES runs(run_id) ON DELETE CASCADE,
+    record_id INTEGER NOT NULL,
+    node_id INTEGER NOT NULL,
+    blob_id INTEGER NOT NULL,
+    decision INTEGER NOT NULL,
+    cast_at INTEGER NOT NULL,
+    cast_proposer_honest INTEGER NOT NULL,
+    included_at INTEGER,
+    equivocation INTEGER NOT NULL,
+    PRIMARY KEY (run_id, record_id),
+    FOREIGN KEY (run_id, node_id) REFERENCES nodes(run_id, node_id),
+    FOREIGN KEY (run_id, blob_id) REFERENCES blobs(run_id, blob_id)
+);
+CREATE INDEX IF NOT EXISTS vote_records_blob ON vote_records(run_id, blob_id);
+";
+
+pub fn open_database<P: AsRef<Path>>(path: P) -> rusqlite::Result<Connection> {
+    let conn = Connection::open(path)?;
+    conn.execute_batch("PRAGMA foreign_keys = ON;")?;
+    conn.execute_batch(SCHEMA)?;
+    Ok(conn)
+}
+
+// Writes one run under `run_id`, replacing any earlier run with the same id
+pub fn export_sqlite(
+    conn: &mut Connection,
+    run_id: &str,
+    result: &SimulationResult,
+) -> rusqlite::Result<()> {
+    let tx = conn.transaction()?;
+    tx.execute("DELETE FROM runs WHERE run_id = ?1", params![run_id])?;
+
+    let settings = serde_json::to_string(&result.settings)
+        .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+    tx.execute(
+        "INSERT INTO runs (run_id, scenario, seed, engine_version, settings)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            run_id,
+            result.scenario.name(),
+            result.settings.seed.map(|seed| seed as i64),
+            ENGINE_VERSION,
+            settings
+        ],
+    )?;
+
+    {
+        let mut insert_node =
+            tx.prepare("INSERT INTO nodes (run_id, node_id, is_honest) VALUES (?1, ?2, ?3)")?;
+        for node in 0..result.settings.total_nodes {
+            insert_node.execute(params![
+                run_id,
+                node as i64,
+                result.honest_nodes.contains(&node)
+            ])?;
+        }
+
+        let mut insert_blob = tx.prepare(
+            "INSERT INTO blobs (run_id, blob_id, votes_honest, votes_malicious, is_confirmed, proposer_status)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        )?;
+        for blob in result.blobs.values() {
+            insert_blob.execute(params![
+                run_id,
+                blob.id as i64,
+                blob.votes_honest as i64,
+                blob.votes_malicious as i64,
+                blob.is_confirmed,
+                blob.proposer_status
+            ])?;
+        }
+
+        let mut insert_record = tx.prepare(
+            "INSERT INTO vote_records
+                (run_id, record_id, node_id, blob_id, decision, cast_at, cast_proposer_honest, included_at,
+                 equivocation)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        )?;
+        for (record_id, record) in result.votes.iter().enumerate() {
+            insert_record.execute(params![
+                run_id,
+                record_id as i64,
+                record.voter as i64,
+                record.blob_id as i64,
+                record.decision,
+                record.cast_at as i64,
+                record.cast_proposer_honest,
+                record.included_at.map(|block| block as i64),
+                record.equivocation
+            ])?;
+        }
+
+        let mut insert_block = tx.prepare(
+            "INSERT INTO blocks (run_id, number, proposer, parent_hash, blob_root, vote_root, hash)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        )?;
+        let mut insert_member = tx.prepare(
+            "INSERT INTO committees (run_id, block_number, position, node_id)
+             VALUES (?1, ?2, ?3, ?4)",
+        )?;
+        let mut insert_vote = tx.prepare(
+            "INSERT INTO votes (run_id, block_number, blob_id, position, node_id, decision)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        )?;
+        let mut insert_buffered = tx.prepare(
+            "INSERT INTO buffered_votes
+                (run_id, block_number, blob_id, position, node_id, cast_at, decision)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        )?;
+
+        for block in &result.blocks {
+            let number = block.number as i64;
+            let header = block.header.as_ref();
+            insert_block.execute(params![
+                run_id,
+                number,
+                block.proposer as i64,
+                header.map(|header| &header.parent_hash),
+                header.map(|header| &header.blob_root),
+                header.map(|header| &header.vote_root),
+                header.map(|header| &header.hash)
+            ])?;
+
+            for (position, &node) in block.selected_nodes.iter().enumerate() {
+                insert_member.execute(params![run_id, number, position as i64, node as i64])?;
+            }
+
+            for (&blob_id, blob_votes) in &block.votes {
+                for (position, vote) in blob_votes.iter().enumerate() {
+                    let node = block.selected_nodes[position];
+                    insert_vote.execute(params![
+                        run_id,
+                        number,
+                        blob_id as i64,
+                        position as i64,
+                        node as i64,
+                        vote
+                    ])?;
+                }
+            }
+
+            for (&blob_id, buffered) in &block.buffered_votes {
+                for (position, vote) in buffered.iter().enumerate() {
+                    insert_buffered.execute(params![
+                        run_id,
+                        number,
+                        blob_id as i64,
+                        position as i64,
+                        vote.voter as i64,
+                        vote.cast_at as i64,
+                        vote.decision
+                    ])?;
+                }
+            }
+        }
+    }
+
+    tx.commit()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Settings;
+    use crate::simulation::{Scenario, Simulation};
+
+    fn count(conn: &Connection, sql: &str) -> usize {
+        conn.query_row(sql, [], |row| row.get::<_, i64>(0)).unwrap() as usize
+    }
+
+    #[test]
+    fn export_writes_every_row_and_replaces_runs() {
+        let result = Simulation::builder()
+            .settings(Settings::for_tests())
+            .scenario(Scenario::VoteCensorship)
+            .run()
+            .unwrap();
+        let mut conn = open_database(":memory:").unwrap();
+        export_sqlite(&mut conn, "a", &result).unwrap();
+
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM nodes"), 20);
+        assert_eq!(
+            count(&conn, "SELECT COUNT(*) FROM nodes WHERE is_honest"),
+            15
+        );
+        assert_eq!(
+            count(&conn, "SELECT COUNT(*) FROM blocks"),
+            result.blocks.len()
+        );
+        assert_eq!(
+            count(&conn, "SELECT COUNT(*) FROM committees"),
+            result
+                .blocks
+                .iter()
+                .map(|block| block.selected_nodes.len())
+                .sum::<usize>()
+        );
+        assert_eq!(
+            count(&conn, "SELECT COUNT(*) FROM buffered_votes"),
+            result
+                .blocks
+                .iter()
+                .flat_map(|block| block.buffered_votes.values())
+                .map(Vec::len)
+                .sum::<usize>()
+        );
+        assert_eq!(
+            count(&conn, "SELECT COUNT(*) FROM vote_records"),
+            result.votes.len()
+        );
+
+        // Honest Yes votes, direct and buffered, add up to the blob tallies
+        let honest_yes = count(
+            &conn,
+            "SELECT (SELECT COUNT(*) FROM votes JOIN nodes USING (run_id, node_id)
+                     WHERE decision = 1 AND is_honest)
+                  + (SELECT COUNT(*) FROM buffered_votes JOIN nodes USING (run_id, node_id)
+                     WHERE decision = 1 AND is_honest)",
+        );
+        assert_eq!(
+            honest_yes,
+            result
+                .blobs
+                .values()
+                .map(|blob| blob.votes_honest)
+                .sum::<usize>()
+        );
+
+        export_sqlite(&mut conn, "a", &result).unwrap();
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM runs"), 1);
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM nodes"), 20);
+        export_sqlite(&mut conn, "b", &result).unwrap();
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM runs"), 2);
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM nodes"), 40);
+    }
+}
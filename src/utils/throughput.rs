@@ -0,0 +1,179 @@
+use crate::models::{Blob, Block};
+use crate::utils::vote_latency::percentile;
+use prettytable::{format, Cell, Row, Table};
+use std::collections::{BTreeMap, HashMap};
+use std::io::{BufWriter, Write};
+
+// Rolling window in blocks, matching `WINDOW` in plot.py
+pub const THROUGHPUT_WINDOW: usize = 50;
+
+#[derive(Debug, Clone)]
+pub struct BlockThroughput {
+    pub block: usize,
+    pub submitted: usize,
+    pub confirmed: usize,
+    // Mean confirmed blobs per block over the trailing window
+    pub rolling_confirmed: f64,
+    // Blobs submitted but not yet confirmed at the end of the block
+    pub queue_length: usize,
+}
+
+pub fn throughput_series(
+    blocks: &[Block],
+    blobs: &HashMap<usize, Blob>,
+    window: usize,
+) -> Vec<BlockThroughput> {
+    let mut submitted: BTreeMap<usize, usize> = BTreeMap::new();
+    let mut confirmed: BTreeMap<usize, usize> = BTreeMap::new();
+    for blob in blobs.values() {
+        *submitted.entry(blob.submitted_at).or_default() += 1;
+        if let Some(confirmed_at) = blob.confirmed_at {
+            *confirmed.entry(confirmed_at).or_default() += 1;
+        }
+    }
+
+    let window = window.max(1);
+    let mut series: Vec<BlockThroughput> = Vec::with_capacity(blocks.len());
+    let mut queue_length = 0;
+    let mut window_sum = 0;
+    let mut previous = 0;
+
+    // Blocks may skip numbers, e.g. slots orphaned by a reorg; their blobs count towards
+    // the next block on the chain
+    for (i, block) in blocks.iter().enumerate() {
+        let since_previous = previous + 1..=block.number;
+        previous = block.number;
+        let submitted: usize = submitted
+            .range(since_previous.clone())
+            .map(|(_, n)| n)
+            .sum();
+        let confirmed: usize = confirmed.range(since_previous).map(|(_, n)| n).sum();
+        queue_length = queue_length + submitted - confirmed;
+
+        window_sum += confirmed;
+        if i >= window {
+            window_sum -= series[i - window].confirmed;
+        }
+
+        series.push(BlockThroughput {
+            block: block.number,
+            submitted,
+            confirmed,
+            rolling_confirmed: window_sum as f64 / (i + 1).min(window) as f64,
+            queue_length,
+        });
+    }
+
+    series
+}
+
+// Confirmation latency, throughput and steady-state queue length of a run
+pub fn create_throughput_table(blocks: &[Block], blobs: &HashMap<usize, Blob>) -> Table {
+    let series = throughput_series(blocks, blobs, THROUGHPUT_WINDOW);
+
+    let mut latencies: Vec<usize> = blobs
+        .values()
+        .filter_map(Blob::confirmation_latency)
+        .collect();
+    latencies.sort_unstable();
+    let mean_latency = if latencies.is_empty() {
+        0.0
+    } else {
+        latencies.iter().sum::<usize>() as f64 / latencies.len() as f64
+    };
+
+    let confirmed: usize = series.iter().map(|point| point.confirmed).sum();
+    let throughput = if series.is_empty() {
+        0.0
+    } else {
+        confirmed as f64 / series.len() as f64
+    };
+
+    // Steady state starts once the first blob has confirmed
+    let warm_up = series
+        .iter()
+        .position(|point| point.confirmed > 0)
+        .unwrap_or(0);
+    let steady = &series[warm_up..];
+    let steady_throughput = if steady.is_empty() {
+        0.0
+    } else {
+        steady.iter().map(|point| point.confirmed).sum::<usize>() as f64 / steady.len() as f64
+    };
+    let mean_queue = if steady.is_empty() {
+        0.0
+    } else {
+        steady.iter().map(|point| point.queue_length).sum::<usize>() as f64 / steady.len() as f64
+    };
+    let max_queue = steady
+        .iter()
+        .map(|point| point.queue_length)
+        .max()
+        .unwrap_or(0);
+
+    let rows = [
+        ("Blobs submitted", blobs.len().to_string()),
+        ("Blobs confirmed", latencies.len().to_string()),
+        (
+            "Mean confirmation latency (blocks)",
+            format!("{:.2}", mean_latency),
+        ),
+        (
+            "P50 confirmation latency (blocks)",
+            percentile(&latencies, 50.0).to_string(),
+        ),
+        (
+            "P90 confirmation latency (blocks)",
+            percentile(&latencies, 90.0).to_string(),
+        ),
+        (
+            "Max confirmation latency (blocks)",
+            latencies.last().copied().unwrap_or(0).to_string(),
+        ),
+        ("Confirmed blobs per block", format!("{:.3}", throughput)),
+        (
+            "Steady-state confirmed blobs per block",
+            format!("{:.3}", steady_throughput),
+        ),
+        (
+            "Steady-state mean queue length",
+            format!("{:.2}", mean_queue),
+        ),
+        ("Steady-state max queue length", max_queue.to_string()),
+    ];
+
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_BOX_CHARS);
+    table.add_row(Row::new(vec![Cell::new("Throughput"), Cell::new("Value")]));
+    for (name, value) in rows {
+        table.add_row(Row::new(vec![Cell::new(name), Cell::new(&value)]));
+    }
+
+    table
+}
+
+// Per-block throughput series for plotting
+pub fn create_throughput_csv<W: Write>(
+    blocks: &[Block],
+    blobs: &HashMap<usize, Blob>,
+    writer: &mut BufWriter<W>,
+) -> std::io::Result<()> {
+    writeln!(
+        writer,
+        "Block,Submitted Blobs,Confirmed Blobs,Rolling Confirmed Per Block,Queue Length"
+    )?;
+
+    for point in throughput_series(blocks, blobs, THROUGHPUT_WINDOW) {
+        writeln!(
+            writer,
+            "{},{},{},{:.4},{}",
+            point.block,
+            point.submitted,
+            point.confirmed,
+            point.rolling_confirmed,
+            point.queue_length
+        )?;
+    }
+    writer.flush()?;
+    Ok(())
+}
@@ -0,0 +1,141 @@
+use crate::models::{Blob, VoteRecord};
+use prettytable::{format, Cell, Row, Table};
+use std::collections::{BTreeMap, HashMap};
+
+// Nearest-rank percentile of an ascending slice
+pub fn percentile(sorted: &[usize], p: f64) -> usize {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = (p / 100.0 * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct LatencySummary {
+    pub votes: usize,
+    pub included: usize,
+    // Never included before the blob confirmed
+    pub lost: usize,
+    // Not included yet when the run ended
+    pub pending: usize,
+    pub mean: f64,
+    pub p50: usize,
+    pub p90: usize,
+    pub p99: usize,
+    pub max: usize,
+}
+
+pub fn summarize_latency<'a, I>(records: I, blobs: &HashMap<usize, Blob>) -> LatencySummary
+where
+    I: IntoIterator<Item = &'a VoteRecord>,
+{
+    let mut summary = LatencySummary::default();
+    let mut latencies = Vec::new();
+
+    for record in records.into_iter().filter(|record| !record.equivocation) {
+        summary.votes += 1;
+        match record.latency() {
+            Some(latency) => latencies.push(latency),
+            None if blobs
+                .get(&record.blob_id)
+                .is_some_and(|blob| blob.is_confirmed) =>
+            {
+                summary.lost += 1
+            }
+            None => summary.pending += 1,
+        }
+    }
+
+    latencies.sort_unstable();
+    summary.included = latencies.len();
+    if !latencies.is_empty() {
+        summary.mean = latencies.iter().sum::<usize>() as f64 / latencies.len() as f64;
+    }
+    summary.p50 = percentile(&latencies, 50.0);
+    summary.p90 = percentile(&latencies, 90.0);
+    summary.p99 = percentile(&latencies, 99.0);
+    summary.max = latencies.last().copied().unwrap_or(0);
+    summary
+}
+
+// Inclusion latency of honest votes, overall and by the honesty of the proposer they were cast under
+pub fn create_vote_latency_table(votes: &[VoteRecord], blobs: &HashMap<usize, Blob>) -> Table {
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_BOX_CHARS);
+
+    table.add_row(Row::new(vec![
+        Cell::new("Honest Votes"),
+        Cell::new("Votes"),
+        Cell::new("Included"),
+        Cell::new("Never Included"),
+        Cell::new("Pending"),
+        Cell::new("Mean"),
+        Cell::new("P50"),
+        Cell::new("P90"),
+        Cell::new("P99"),
+        Cell::new("Max"),
+    ]));
+
+    let honest = || votes.iter().filter(|vote| vote.voter_honest);
+    let groups = [
+        ("All", summarize_latency(honest(), blobs)),
+        (
+            "Cast under honest proposer",
+            summarize_latency(honest().filter(|vote| vote.cast_proposer_honest), blobs),
+        ),
+        (
+            "Cast under malicious proposer",
+            summarize_latency(honest().filter(|vote| !vote.cast_proposer_honest), blobs),
+        ),
+    ];
+
+    for (name, summary) in groups {
+        table.add_row(Row::new(vec![
+            Cell::new(name),
+            Cell::new(&summary.votes.to_string()),
+            Cell::new(&summary.included.to_string()),
+            Cell::new(&summary.lost.to_string()),
+            Cell::new(&summary.pending.to_string()),
+            Cell::new(&format!("{:.2}", summary.mean)),
+            Cell::new(&summary.p50.to_string()),
+            Cell::new(&summary.p90.to_string()),
+            Cell::new(&summary.p99.to_string()),
+            Cell::new(&summary.max.to_string()),
+        ]));
+    }
+
+    table
+}
+
+// Histogram of inclusion latency (in blocks) over included honest votes
+pub fn create_vote_latency_histogram(votes: &[VoteRecord]) -> Table {
+    let mut histogram: BTreeMap<usize, usize> = BTreeMap::new();
+    for latency in votes
+        .iter()
+        .filter(|vote| vote.voter_honest && !vote.equivocation)
+        .filter_map(VoteRecord::latency)
+    {
+        *histogram.entry(latency).or_default() += 1;
+    }
+    let total: usize = histogram.values().sum();
+
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_BOX_CHARS);
+
+    table.add_row(Row::new(vec![
+        Cell::new("Latency (blocks)"),
+        Cell::new("Honest Votes"),
+        Cell::new("Share"),
+    ]));
+
+    for (latency, count) in histogram {
+        table.add_row(Row::new(vec![
+            Cell::new(&latency.to_string()),
+            Cell::new(&count.to_string()),
+            Cell::new(&format!("{:.2}%", count as f64 / total as f64 * 100.0)),
+        ]));
+    }
+
+    table
+}
@@ -0,0 +1,191 @@
+use crate::models::{Blob, InvalidVote, VoteMessage, VoteValidationMetrics};
+use prettytable::{format, Cell, Row, Table};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+// Applies the vote rules in order: signature, committee membership, no duplicate,
+// known blob, then unexpired blob. A round is the block whose committee casts the
+// votes; committees are registered per round, and accepted votes are remembered per
+// round so each member votes at most once on each blob.
+#[derive(Default)]
+pub struct VoteValidator {
+    committees: HashMap<usize, HashSet<usize>>,
+    // Decision of every accepted vote, by round and (voter, blob)
+    accepted: HashMap<usize, HashMap<(usize, usize), bool>>,
+}
+
+impl VoteValidator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Registers the committee of the block numbered `round`
+    pub fn open_round(&mut self, round: usize, committee: &[usize]) {
+        self.committees
+            .insert(round, committee.iter().copied().collect());
+    }
+
+    // Checks a vote on `blob`, None if no such blob exists, for inclusion in block
+    // `included_at`, and remembers it if it passes
+    pub fn check(
+        &mut self,
+        message: &VoteMessage,
+        blob: Option<&Blob>,
+        included_at: usize,
+    ) -> Result<(), InvalidVote> {
+        if !message.verify() {
+            return Err(InvalidVote::BadSignature);
+        }
+        if !self
+            .committees
+            .get(&message.block)
+            .is_some_and(|committee| committee.contains(&message.voter))
+        {
+            return Err(InvalidVote::NotInCommittee);
+        }
+        let key = (message.voter, message.blob_id);
+        if self
+            .accepted
+            .get(&message.block)
+            .is_some_and(|accepted| accepted.contains_key(&key))
+        {
+            return Err(InvalidVote::Duplicate);
+        }
+        let blob = blob
+            .filter(|blob| blob.id == message.blob_id && blob.submitted_at <= message.block)
+            .ok_or(InvalidVote::UnknownBlob)?;
+        if blob
+            .confirmed_at
+            .is_some_and(|confirmed_at| confirmed_at < included_at)
+        {
+            return Err(InvalidVote::ExpiredBlob);
+        }
+        self.accepted
+            .entry(message.block)
+            .or_default()
+            .insert(key, message.decision);
+        Ok(())
+    }
+
+    // Whether `message` is signed by its voter and contradicts the vote the same voter
+    // already cast on the blob in the same round, which proves an equivocation
+    pub fn conflicts(&self, message: &VoteMessage) -> bool {
+        message.verify()
+            && self
+                .accepted
+                .get(&message.block)
+                .and_then(|accepted| accepted.get(&(message.voter, message.blob_id)))
+                .is_some_and(|&decision| decision != message.decision)
+    }
+}
+
+// Included votes that passed validation and the adversary's rejected attempts
+pub fn create_vote_validation_table(metrics: &VoteValidationMetrics) -> Table {
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_BOX_CHARS);
+    table.add_row(Row::new(vec![
+        Cell::new("Vote validation"),
+        Cell::new("Attempted"),
+        Cell::new("Rejected"),
+    ]));
+    table.add_row(Row::new(vec![
+        Cell::new("votes for inclusion"),
+        Cell::new(&(metrics.accepted + metrics.rejected_included).to_string()),
+        Cell::new(&metrics.rejected_included.to_string()),
+    ]));
+    for kind in InvalidVote::ALL {
+        let count = |counts: &BTreeMap<InvalidVote, usize>| {
+            counts.get(&kind).copied().unwrap_or_default().to_string()
+        };
+        table.add_row(Row::new(vec![
+            Cell::new(kind.name()),
+            Cell::new(&count(&metrics.attempted)),
+            Cell::new(&count(&metrics.rejected)),
+        ]));
+    }
+
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Validator with committee [1, 2] in round 4 and blob 0 submitted in block 2
+    fn validator() -> (VoteValidator, Blob) {
+        let mut validator = VoteValidator::new();
+        validator.open_round(4, &[1, 2]);
+        (validator, Blob::new(0, 2, 0))
+    }
+
+    #[test]
+    fn rules_apply_in_order() {
+        let (mut validator, blob) = validator();
+        assert_eq!(
+            validator.check(&VoteMessage::sign(1, 0, 4, true), Some(&blob), 4),
+            Ok(())
+        );
+
+        // A forged signature is rejected before committee membership is looked at
+        let mut forged = VoteMessage::sign(3, 0, 4, true);
+        forged.decision = false;
+        assert_eq!(
+            validator.check(&forged, Some(&blob), 4),
+            Err(InvalidVote::BadSignature)
+        );
+        assert_eq!(
+            validator.check(&VoteMessage::sign(3, 0, 4, true), Some(&blob), 4),
+            Err(InvalidVote::NotInCommittee)
+        );
+        assert_eq!(
+            validator.check(&VoteMessage::sign(2, 0, 5, true), Some(&blob), 5),
+            Err(InvalidVote::NotInCommittee)
+        );
+        assert_eq!(
+            validator.check(&VoteMessage::sign(1, 0, 4, false), Some(&blob), 4),
+            Err(InvalidVote::Duplicate)
+        );
+        assert_eq!(
+            validator.check(&VoteMessage::sign(2, 9, 4, true), None, 4),
+            Err(InvalidVote::UnknownBlob)
+        );
+    }
+
+    #[test]
+    fn blob_must_exist_and_be_open() {
+        let (mut validator, mut blob) = validator();
+
+        // Submitted after the round that voted on it
+        blob.submitted_at = 5;
+        assert_eq!(
+            validator.check(&VoteMessage::sign(1, 0, 4, true), Some(&blob), 4),
+            Err(InvalidVote::UnknownBlob)
+        );
+
+        // Buffered into a block after the blob confirmed
+        blob.submitted_at = 2;
+        blob.confirmed_at = Some(5);
+        assert_eq!(
+            validator.check(&VoteMessage::sign(1, 0, 4, true), Some(&blob), 6),
+            Err(InvalidVote::ExpiredBlob)
+        );
+        assert_eq!(
+            validator.check(&VoteMessage::sign(1, 0, 4, true), Some(&blob), 5),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn conflicting_vote_proves_equivocation() {
+        let (mut validator, blob) = validator();
+        validator
+            .check(&VoteMessage::sign(1, 0, 4, true), Some(&blob), 4)
+            .unwrap();
+
+        assert!(validator.conflicts(&VoteMessage::sign(1, 0, 4, false)));
+        assert!(!validator.conflicts(&VoteMessage::sign(1, 0, 4, true)));
+        assert!(!validator.conflicts(&VoteMessage::sign(2, 0, 4, false)));
+        let mut forged = VoteMessage::sign(1, 0, 4, true);
+        forged.decision = false;
+        assert!(!validator.conflicts(&forged));
+    }
+}